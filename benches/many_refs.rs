@@ -0,0 +1,112 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Times git-tree on a synthetic repository with many refs: `LOCALS` local
+//! branches, each with a remote-tracking branch, plus enough other remote
+//! branches to make `REMOTES` in total. Run with `cargo bench`; criterion
+//! compares each run with the previous one, or with a baseline saved by
+//! `cargo bench -- --save-baseline <name>` when given `-- --baseline <name>`.
+
+use core::fmt::Write as _;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::env::temp_dir;
+use std::fs::remove_dir_all;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{id, Command, Stdio};
+
+/// How many local branches the repository has, on a linear history.
+const LOCALS: usize = 200;
+
+/// How many remote branches the repository has.
+const REMOTES: usize = 50_000;
+
+/// The committer timestamp of the first commit.
+const FIRST_COMMIT_TIME: usize = 1_700_000_000;
+
+/// Runs `git <args>` in `dir`, feeding it `input`, and returns its output.
+fn git(dir: &Path, args: &[&str], input: &str) -> String {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run git");
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(input.as_bytes()).expect("git stdin write failed");
+    drop(stdin);
+    let output = child.wait_with_output().expect("failed to wait for git");
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8(output.stdout).expect("non-utf-8 git output")
+}
+
+/// Creates the repository in `dir`.
+fn create_repository(dir: &Path) {
+    drop(remove_dir_all(dir));
+    let dir_name = dir.to_str().expect("non-utf-8 temporary directory");
+    git(Path::new("."), &["init", "--quiet", dir_name], "");
+    let mut commits = String::new();
+    for i in 0..LOCALS {
+        let time = FIRST_COMMIT_TIME.saturating_add(i);
+        writeln!(commits, "commit refs/heads/branch{i}").unwrap();
+        writeln!(commits, "committer Bench <bench@example.com> {time} +0000").unwrap();
+        writeln!(commits, "data <<END\ncommit {i}\nEND").unwrap();
+        if let Some(parent) = i.checked_sub(1) {
+            writeln!(commits, "from refs/heads/branch{parent}").unwrap();
+        }
+        commits.push('\n');
+    }
+    git(dir, &["fast-import", "--quiet"], &commits);
+    let head = format!("refs/heads/branch{}", LOCALS.saturating_sub(1));
+    git(dir, &["symbolic-ref", "HEAD", &head], "");
+    // The remote branches are spread over the same commits.
+    let listing = git(dir, &["for-each-ref", "--format=%(objectname) %(refname:lstrip=2)"], "");
+    let locals: Vec<_> = listing.lines().filter_map(|line| line.split_once(' ')).collect();
+    let mut updates = String::new();
+    for (i, &(id, name)) in locals.iter().cycle().take(REMOTES).enumerate() {
+        if i < locals.len() {
+            writeln!(updates, "create refs/remotes/origin/{name} {id}").unwrap();
+        } else {
+            writeln!(updates, "create refs/remotes/origin/other{i} {id}").unwrap();
+        }
+    }
+    git(dir, &["update-ref", "--stdin"], &updates);
+    git(dir, &["pack-refs", "--all"], "");
+}
+
+/// Runs git-tree with `args` in `dir`, discarding its output.
+fn git_tree(dir: &Path, args: &[&str]) {
+    let status = Command::new(env!("CARGO_BIN_EXE_git-tree"))
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_PAGER", "cat")
+        .stdout(Stdio::null())
+        .status()
+        .expect("failed to run git-tree");
+    assert!(status.success(), "git-tree {args:?} failed");
+}
+
+fn many_refs(criterion: &mut Criterion) {
+    let dir = temp_dir().join(format!("git-tree-bench-{}", id()));
+    create_repository(&dir);
+    // `args` only chooses the commits; the default mode also runs git log.
+    criterion.bench_function("args", |bencher| bencher.iter(|| git_tree(&dir, &["args"])));
+    criterion.bench_function("log", |bencher| bencher.iter(|| git_tree(&dir, &[])));
+    remove_dir_all(dir).expect("failed to remove the repository");
+}
+
+criterion_group!(benches, many_refs);
+criterion_main!(benches);
@@ -0,0 +1,104 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use crate::trunk::default_branch;
+use std::collections::HashMap;
+use std::io::{stdout, Write as _};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Histogram buckets: (exclusive upper bound on age in seconds, label).
+const BUCKETS: [(u64, &str); 6] = [
+    (604_800, "< 1 week"),
+    (2_419_200, "1-4 weeks"),
+    (7_862_400, "1-3 months"),
+    (15_724_800, "3-6 months"),
+    (31_449_600, "6-12 months"),
+    (u64::MAX, "> 1 year"),
+];
+
+/// The longest bar drawn in the histogram.
+const MAX_BAR: usize = 50;
+
+/// Implements `git-tree ages`, which reports how long ago each interesting
+/// branch diverged from the default branch.
+pub fn ages(git: &GitRunner, selection: &Selection) {
+    let default = default_branch(git).expect("unable to determine the default branch");
+    // (branch, merge base with the default branch)
+    let mut branches = vec![];
+    let mut unrelated = vec![];
+    for branch in &selection.interesting_branches {
+        if *branch == default {
+            continue;
+        }
+        match git.query("merge-base", &[&default, branch]) {
+            None => unrelated.push(branch.as_str()),
+            Some(base) => branches.push((branch.as_str(), base)),
+        }
+    }
+    // Map from merge base ID to (abbreviated ID, committer timestamp).
+    let mut bases: HashMap<&str, (&str, u64)> = HashMap::new();
+    let output = if branches.is_empty() {
+        String::new()
+    } else {
+        let show = git
+            .internal("show")
+            .args(["--no-patch", "--format=%H %h %ct"])
+            .args(branches.iter().map(|entry| &entry.1))
+            .arg("--")
+            .output()
+            .expect("failed to run git");
+        assert!(show.status.success(), "git returned unsuccessful status {}", show.status);
+        String::from_utf8(show.stdout).expect("non-utf-8 git output")
+    };
+    for line in output.lines() {
+        let mut fields = line.split(' ');
+        let mut field = || fields.next().expect("truncated git show output");
+        let id = field();
+        let short_id = field();
+        let timestamp = field().parse().expect("invalid git timestamp");
+        bases.insert(id, (short_id, timestamp));
+    }
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("clock before 1970").as_secs();
+    // (age in seconds, branch, abbreviated merge base ID)
+    let mut ages: Vec<_> = branches
+        .iter()
+        .map(|entry| {
+            let &(short_id, timestamp) = bases.get(entry.1.as_str()).unwrap();
+            (now.saturating_sub(timestamp), entry.0, short_id)
+        })
+        .collect();
+    ages.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut out = stdout().lock();
+    writeln!(out, "Age of divergence from {default}:").written();
+    let label_width = BUCKETS.iter().map(|&(_, label)| label.len()).max().unwrap();
+    let mut lower = 0;
+    for &(upper, label) in &BUCKETS {
+        let count = ages.iter().filter(|&&(age, _, _)| lower <= age && age < upper).count();
+        let line = format!("  {label:label_width$} {count:>5} {}", "#".repeat(count.min(MAX_BAR)));
+        writeln!(out, "{}", line.trim_end()).written();
+        lower = upper;
+    }
+    writeln!(out).written();
+    for (age, branch, short_id) in ages {
+        writeln!(out, "{:>6}d  {branch} (diverged at {short_id})", age.div_euclid(86_400))
+            .written();
+    }
+    for branch in unrelated {
+        writeln!(out, "     -   {branch} (no common history)").written();
+    }
+}
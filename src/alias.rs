@@ -0,0 +1,115 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::args::is_subcommand;
+use crate::git_runner::GitRunner;
+use crate::settings::Settings;
+use std::ffi::OsString;
+
+/// Returns a runner for the repository the command line `args` selects (with
+/// --git-dir, --work-tree, or the first --repo), whose settings hold the
+/// aliases and default arguments. Only the options before `--` are examined.
+pub fn runner(args: &[OsString]) -> GitRunner {
+    let (mut no_replace_objects, mut repo, mut git_dir, mut work_tree) = (None, None, None, None);
+    let mut args = args.iter().map_while(|arg| arg.to_str()).take_while(|&arg| arg != "--");
+    while let Some(arg) = args.next() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value)),
+            _ => (arg, None),
+        };
+        let mut value = || inline.or_else(|| args.next()).map(String::from);
+        match name {
+            "--no-replace-objects" => no_replace_objects = Some(true),
+            "--replace-objects" => no_replace_objects = Some(false),
+            "--repo" => repo = repo.or_else(value),
+            "--git-dir" => git_dir = value(),
+            "--work-tree" => work_tree = value(),
+            _ => {}
+        }
+    }
+    GitRunner::new(no_replace_objects, repo, git_dir, work_tree)
+}
+
+/// Expands a `tree.alias.<name>` alias used as the first argument, returning the
+/// resulting arguments. Like git's aliases, an alias is only recognized as the
+/// first argument and cannot redefine a subcommand, but its value may contain
+/// git-tree's own options.
+pub fn expand(settings: &Settings, mut args: Vec<OsString>) -> Vec<OsString> {
+    let Some(name) = args.first().and_then(|first| first.to_str()) else { return args };
+    if name.starts_with('-') || is_subcommand(name) {
+        return args;
+    }
+    let Some(value) = settings.get(&format!("tree.alias.{name}")) else {
+        return args;
+    };
+    args.splice(..1, split(value).into_iter().map(Into::into));
+    args
+}
+
+/// Inserts the arguments in `tree.defaultArgs` before `args`, so that arguments
+/// given on the command line come later and take precedence.
+pub fn with_defaults(settings: &Settings, mut args: Vec<OsString>) -> Vec<OsString> {
+    if let Some(value) = settings.get("tree.defaultArgs") {
+        args.splice(..0, split(value).into_iter().map(Into::into));
+    }
+    args
+}
+
+/// Splits an alias' value into arguments at whitespace. Single or double quotes
+/// may be used to include whitespace in an argument.
+fn split(value: &str) -> Vec<String> {
+    let mut split = vec![];
+    let mut current: Option<String> = None;
+    let mut quote = None;
+    for c in value.chars() {
+        match (quote, c) {
+            (Some(open), _) if c == open => quote = None,
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.get_or_insert_default();
+            }
+            (None, _) if c.is_whitespace() => split.extend(current.take()),
+            _ => current.get_or_insert_default().push(c),
+        }
+    }
+    assert!(quote.is_none(), "unterminated quote in git-tree alias: {value}");
+    split.extend(current);
+    split
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split;
+
+    #[test]
+    fn splits_at_whitespace() {
+        assert_eq!(split("  --mine\t--stash  -n 5 "), ["--mine", "--stash", "-n", "5"]);
+        assert!(split("").is_empty());
+    }
+
+    #[test]
+    fn quotes_group_arguments() {
+        assert_eq!(
+            split(r#"--grep "fix bug" --author='A B'"#),
+            ["--grep", "fix bug", "--author=A B"]
+        );
+        assert_eq!(split(r#"'say "hi"' """#), ["say \"hi\"", ""]);
+    }
+
+    #[test]
+    #[should_panic(expected = "unterminated quote in git-tree alias: --grep 'x")]
+    fn rejects_unterminated_quotes() {
+        split("--grep 'x");
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::branch_stats::branch_stats;
+use crate::codeowners::reviewers as codeowners;
+use crate::commits::base_of;
+use crate::decorations::{hidden_refs, hide_patterns};
+use crate::dependencies::dependencies;
+use crate::git_runner::GitRunner;
+use crate::large_commits::large_commits;
+use crate::lint::lint as lint_messages;
+use crate::selection::Selection;
+use crate::tags::release_tags;
+use std::collections::{HashMap, HashSet};
+
+/// Per-commit information shown by git-tree's own output formats, in addition
+/// to the commits themselves.
+pub struct Annotations {
+    /// Map from commit ID to the release tags pointing at that commit.
+    pub release_tags: HashMap<String, Vec<String>>,
+
+    /// Set if --mark-contained-in was passed.
+    pub containment: Option<Containment>,
+
+    /// Map from commit ID to the user-defined labels (from --label and
+    /// `tree.label.<name>`) on that commit.
+    pub labels: HashMap<String, Vec<String>>,
+
+    /// Map from commit ID to the problems found in that commit: those --lint
+    /// found in its message, and whether it is larger than `tree.largeCommit`.
+    pub problems: HashMap<String, Vec<String>>,
+
+    /// Map from interesting branch to the owners (from CODEOWNERS) of the
+    /// paths it changes, if --reviewers was passed.
+    pub reviewers: HashMap<String, Vec<String>>,
+
+    /// Map from merge base ID to the interesting branches that fork from it.
+    pub base_of: HashMap<String, Vec<String>>,
+
+    /// Map from interesting branch to the interesting branches it depends on
+    /// (see `dependencies`).
+    pub dependencies: HashMap<String, Vec<String>>,
+
+    /// Map from interesting branch to how far it is ahead of and behind its
+    /// upstream (see `branch_stats`).
+    pub stats: HashMap<String, String>,
+
+    /// Short names of the refs whose labels `tree.decorateHide` hides. Hidden
+    /// release tags are already omitted from `release_tags`.
+    pub hidden: HashSet<String>,
+}
+
+impl Annotations {
+    /// `contained_in` is the value of --mark-contained-in, if it was passed.
+    /// `labels` are the (commit, text) pairs passed to --label. `lint`,
+    /// `reviewers`, and `no_stats` are whether --lint, --reviewers, and
+    /// --no-stats were passed.
+    pub fn load(
+        git: &GitRunner,
+        selection: &Selection,
+        contained_in: Option<String>,
+        labels: &[(String, String)],
+        lint: bool,
+        reviewers: bool,
+        no_stats: bool,
+    ) -> Self {
+        let hidden = hidden_refs(git, &hide_patterns(git));
+        let mut release_tags = release_tags(git);
+        release_tags.retain(|_, tags| {
+            tags.retain(|tag| !hidden.contains(tag));
+            !tags.is_empty()
+        });
+        let mut problems = if lint { lint_messages(git, selection) } else { HashMap::new() };
+        for (id, warning) in large_commits(git, selection) {
+            problems.entry(id).or_default().push(warning);
+        }
+        Self {
+            release_tags,
+            containment: contained_in.map(|refname| Containment::load(git, selection, refname)),
+            labels: load_labels(git, labels),
+            problems,
+            reviewers: if reviewers { codeowners(git, selection) } else { HashMap::new() },
+            base_of: base_of(git, selection),
+            dependencies: dependencies(git, selection),
+            stats: branch_stats(git, selection, no_stats),
+            hidden,
+        }
+    }
+}
+
+/// Returns the user-defined labels, keyed by commit ID. `cli` are the (commit,
+/// text) pairs passed to --label.
+fn load_labels(git: &GitRunner, cli: &[(String, String)]) -> HashMap<String, Vec<String>> {
+    let resolve = |commit: &str| {
+        git.query("rev-parse", &["--verify", "--quiet", &format!("{commit}^{{commit}}")])
+    };
+    let mut labels: HashMap<String, Vec<String>> = HashMap::new();
+    // Each value is `<commit>=<text>`. Labels in the config tend to outlive
+    // the commits they name (e.g. a deleted branch), so ones that do not
+    // resolve are skipped rather than treated as errors.
+    let config = git.settings().get_with_prefix("tree.label.");
+    for (commit, text) in config.into_iter().filter_map(|label| label.split_once('=')) {
+        if let Some(id) = resolve(commit) {
+            labels.entry(id).or_default().push(text.into());
+        }
+    }
+    for label in cli {
+        let id = resolve(&label.0);
+        assert!(id.is_some(), "--label: {} is not a commit", label.0);
+        labels.entry(id.unwrap()).or_default().push(label.1.clone());
+    }
+    labels
+}
+
+/// Which displayed commits are reachable from a particular ref.
+pub struct Containment {
+    refname: String,
+
+    /// The displayed commits that are *not* reachable from `refname`. This is
+    /// usually much smaller than the set of commits that are.
+    missing: HashSet<String>,
+}
+
+impl Containment {
+    fn load(git: &GitRunner, selection: &Selection, refname: String) -> Self {
+        let output = git
+            .internal("rev-list")
+            // This must precede the revisions, which contain --not.
+            .arg(format!("^{refname}"))
+            .args(selection.revisions())
+            .arg("--")
+            .output()
+            .expect("failed to run git");
+        assert!(output.status.success(), "git returned unsuccessful status {}", output.status);
+        let missing = String::from_utf8(output.stdout)
+            .expect("non-utf-8 git output")
+            .lines()
+            .map(Into::into)
+            .collect();
+        Self { refname, missing }
+    }
+
+    pub fn refname(&self) -> &str {
+        &self.refname
+    }
+
+    /// Returns whether the displayed commit `id` is reachable from `refname`.
+    pub fn contains(&self, id: &str) -> bool {
+        !self.missing.contains(id)
+    }
+}
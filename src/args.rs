@@ -0,0 +1,762 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commits::Merges;
+use crate::heuristics::Profile;
+use alloc::vec::IntoIter;
+use core::iter::Peekable;
+use core::mem::take;
+use std::ffi::OsString;
+
+/// Printed for --help before the list of subcommands. The README has the
+/// details.
+const HELP: &str = "\
+usage: git-tree [<options>] [<git log arguments>] [-- <paths>]
+       git-tree [<options>] <subcommand> [<arguments>]
+
+Runs git log on the interesting commits: the tips of the local branches, the
+remote branches with the same names as local branches or that local branches
+track or push to, HEAD, and the detached HEADs of other worktrees, along with
+their merge bases and the commits between them.
+Arguments git-tree does not recognize are passed to git log.
+
+Options:
+  --changelog[=<branch>]       print a Markdown changelog per branch
+  --format=<format>            print graph, markdown, ndjson, or svg
+  --linear                     describe the graph in words
+  --mark-contained-in <ref>    mark which commits <ref> contains
+  --ghosts                     draw each branch's pre-rebase tip (svg)
+  --label <commit>=<text>      pin a label onto a commit (markdown, svg)
+  --lint                       mark commit message problems (markdown, svg)
+  --reviewers                  show each branch's code owners (markdown)
+  --staged                     draw staged changes (svg)
+  --no-stats                   skip ahead/behind counts (markdown, --linear)
+  --path <pathspec>            only show commits touching <pathspec>
+  --no-merges, --merges-only   hide merge commits, or all other commits
+  --mode=minimal|default|full  choose fewer or more branches to consider
+  --include-ref <ref>          also consider <ref> (e.g. a tag or refs/stash)
+  --stash                      also consider every stash entry
+  --review-refs                also consider fetched refs/pull, refs/changes
+  --exclude-branch <glob>      do not consider matching branches
+  --only-branch <glob>         only consider matching branches
+  --branch-since <duration>    only consider branches committed to since (30d)
+  --branch-author <pattern>    only consider branches whose tips they authored
+  --mine                       only consider branches whose tips you authored
+  --max-branches <n>           only consider the n most recent branches
+  --hide-merged[=<base>]       do not consider branches merged into <base>
+  --base <ref>                 cut the history off at <ref>, not the merge bases
+  --merge-base-mode=<mode>     octopus (default) or pairwise, with HEAD
+  --head <ref>                 compute the merge bases with <ref> as HEAD
+  --no-remotes                 do not consider remote branches
+  --remote <name>              only consider this remote's branches
+  --all-remotes                consider every remote branch
+  --remote-view <name>         only consider this remote's branches, not HEAD
+  --recent <n>                 scan n reflog entries if HEAD is detached (50)
+  --pull-requests              fetch pull requests opened from the branches
+  --refs-from-stdin            read the interesting branches from stdin
+  --[no-]replace-objects       honor or ignore git replace refs
+  --git-dir <path>             use this repository
+  --work-tree <path>           use this working tree
+  --repo <path>                run in each listed repository
+  -h, --help                   print this help
+
+Subcommands:
+";
+
+/// Printed for --help after the list of subcommands.
+const HELP_SETTINGS: &str = "
+Settings are read from git config under tree.*, and from git-tree.toml in
+$XDG_CONFIG_HOME/git-tree/ and in the .git directory (see the README).
+";
+
+/// What git-tree does with the selected commits. Without a mode, git-tree runs
+/// `git log`.
+#[derive(Clone)]
+pub enum Mode {
+    /// The `ages` subcommand.
+    Ages,
+
+    /// The `args` subcommand.
+    Args,
+
+    /// `bisect-hints [<bad>]`, with the bad ref if one was given.
+    BisectHints(Option<String>),
+
+    /// `blame-base <branch>`.
+    BlameBase(String),
+
+    /// `bundle <file>`.
+    Bundle(String),
+
+    /// `--changelog[=<branch>]`, with the branch if one was given.
+    Changelog(Option<String>),
+
+    /// `compare <base>`.
+    Compare(String),
+
+    /// `diff-branches <a> <b>`.
+    DiffBranches((String, String)),
+
+    /// `export --script`.
+    Export,
+
+    /// `--format=graph`.
+    Graph,
+
+    /// The `is-ancestor` subcommand.
+    IsAncestor,
+
+    /// `--linear`.
+    Linear,
+
+    /// `--format=markdown`.
+    Markdown,
+
+    /// `--format=ndjson`.
+    Ndjson,
+
+    /// `pick [<n> [<command>...]]`, with the number and command if given.
+    Pick(Option<(usize, Vec<OsString>)>),
+
+    /// `search <regex>`.
+    Search(String),
+
+    /// The `select` subcommand.
+    Select,
+
+    /// The `stats` subcommand.
+    Stats,
+
+    /// The `status` subcommand.
+    Status,
+
+    /// `--format=svg`.
+    Svg,
+
+    /// The `switch` subcommand.
+    Switch,
+
+    /// The `timeline` subcommand.
+    Timeline,
+
+    /// `undo-info <branch>`.
+    UndoInfo(String),
+}
+
+/// git-tree's command-line arguments. Arguments that git-tree does not
+/// recognize are forwarded to `git log`.
+#[allow(clippy::struct_excessive_bools, reason = "each bool is an independent flag")]
+#[derive(Clone, Default)]
+pub struct Args {
+    /// `Some(true)` for --no-replace-objects, `Some(false)` for
+    /// --replace-objects, `None` if neither was passed.
+    pub no_replace_objects: Option<bool>,
+
+    pub mode: Option<Mode>,
+
+    /// Ref passed to --mark-contained-in.
+    pub mark_contained_in: Option<String>,
+
+    /// Refs passed to --include-ref, in order.
+    pub include_refs: Vec<String>,
+
+    /// Whether --stash was passed.
+    pub stash: bool,
+
+    /// Whether --review-refs was passed.
+    pub review_refs: bool,
+
+    /// Patterns passed to --exclude-branch and --only-branch, in order.
+    pub exclude_branches: Vec<String>,
+    pub only_branches: Vec<String>,
+
+    /// The --branch-since duration, in seconds.
+    pub branch_since: Option<u64>,
+
+    /// Patterns passed to --branch-author, in order.
+    pub branch_authors: Vec<String>,
+
+    /// Whether --mine was passed.
+    pub mine: bool,
+
+    /// The value of --max-branches.
+    pub max_branches: Option<usize>,
+
+    /// The --hide-merged base, empty if it was passed without one.
+    pub hide_merged: Option<String>,
+
+    /// Refs passed to --base, in order.
+    pub bases: Vec<String>,
+
+    /// The value of --head.
+    pub head: Option<String>,
+
+    /// Whether --merge-base-mode=pairwise was passed.
+    pub pairwise: bool,
+
+    /// Whether --ghosts was passed.
+    pub ghosts: bool,
+
+    /// Whether --lint was passed.
+    pub lint: bool,
+
+    /// Whether --reviewers was passed.
+    pub reviewers: bool,
+
+    /// (commit, text) pairs passed to --label, in order.
+    pub labels: Vec<(String, String)>,
+
+    /// Whether --help (or -h) was passed.
+    pub help: bool,
+
+    /// Whether --no-remotes was passed.
+    pub no_remotes: bool,
+
+    /// Remotes passed to --remote, in order.
+    pub remotes: Vec<String>,
+
+    /// Whether --all-remotes was passed.
+    pub all_remotes: bool,
+
+    /// The value of --remote-view.
+    pub remote_view: Option<String>,
+
+    /// Values of --git-dir and --work-tree.
+    pub git_dir: Option<String>,
+    pub work_tree: Option<String>,
+
+    /// Repositories passed to --repo, in order.
+    pub repos: Vec<String>,
+
+    /// Pathspecs passed to --path, in order.
+    pub paths: Vec<String>,
+
+    /// `Some(Merges::Hidden)` for --no-merges, `Some(Merges::Only)` for
+    /// --merges-only, `None` if neither was passed.
+    pub merges: Option<Merges>,
+
+    /// How many reflog entries to scan for recently checked-out refs when
+    /// HEAD is detached (--recent).
+    pub recent: usize,
+
+    /// The heuristic selected by --mode.
+    pub profile: Profile,
+
+    /// Whether --pull-requests was passed.
+    pub pull_requests: bool,
+
+    /// Whether --refs-from-stdin was passed.
+    pub refs_from_stdin: bool,
+
+    /// Whether --staged was passed.
+    pub staged: bool,
+
+    /// Whether --no-stats was passed.
+    pub no_stats: bool,
+
+    /// Arguments to forward to `git log`.
+    pub passthrough: Vec<OsString>,
+
+    /// Negative revisions from the `git log` arguments (`^<rev>`, or `<rev>`
+    /// after --not), without their `^`. They are removed from `passthrough`
+    /// and subtracted from git-tree's own revisions instead.
+    pub negatives: Vec<String>,
+
+    /// Positive revisions from the `git log` arguments, other than ranges.
+    /// Those that name commits are treated as interesting branches.
+    pub positives: Vec<String>,
+}
+
+impl Args {
+    /// Parses git-tree's command line arguments (not including the program
+    /// name).
+    pub fn parse(args: Vec<OsString>) -> Self {
+        // --recent scans 50 reflog entries unless told otherwise.
+        let mut parsed = Self { recent: 50, ..Self::default() };
+        let mut args = args.into_iter().peekable();
+        // Whether a non-option argument has been seen.
+        let mut positional = false;
+        while let Some(arg) = args.next() {
+            let Some(text) = arg.to_str() else {
+                parsed.passthrough.push(arg);
+                continue;
+            };
+            // Split --option=value into the option and its value.
+            let (name, inline) = match text.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value)),
+                _ => (text, None),
+            };
+            match (name, inline) {
+                // Everything after -- is a path, which belongs to git log.
+                ("--", None) => {
+                    parsed.passthrough.push(arg);
+                    break;
+                }
+                ("--no-replace-objects", None) => parsed.no_replace_objects = Some(true),
+                ("--replace-objects", None) => parsed.no_replace_objects = Some(false),
+                ("--changelog", branch) => {
+                    parsed.mode = Some(Mode::Changelog(branch.map(Into::into)));
+                }
+                // Other --format values are git log formats.
+                ("--format", Some("graph")) => parsed.mode = Some(Mode::Graph),
+                ("--format", Some("markdown")) => parsed.mode = Some(Mode::Markdown),
+                ("--format", Some("ndjson")) => parsed.mode = Some(Mode::Ndjson),
+                ("--format", Some("svg")) => parsed.mode = Some(Mode::Svg),
+                ("--linear", None) => parsed.mode = Some(Mode::Linear),
+                ("--mark-contained-in", _) => {
+                    parsed.mark_contained_in = Some(value(inline, &mut args));
+                }
+                ("--include-ref", _) => parsed.include_refs.push(value(inline, &mut args)),
+                ("--stash", None) => parsed.stash = true,
+                ("--review-refs", None) => parsed.review_refs = true,
+                ("--exclude-branch", _) => parsed.exclude_branches.push(value(inline, &mut args)),
+                ("--only-branch", _) => parsed.only_branches.push(value(inline, &mut args)),
+                ("--branch-since", _) => {
+                    parsed.branch_since = Some(duration(&value(inline, &mut args)));
+                }
+                ("--branch-author", _) => parsed.branch_authors.push(value(inline, &mut args)),
+                ("--mine", None) => parsed.mine = true,
+                ("--hide-merged", base) => {
+                    parsed.hide_merged = Some(base.unwrap_or_default().into());
+                }
+                ("--mode", _) => parsed.profile = profile(&value(inline, &mut args)),
+                ("--head", _) => parsed.head = Some(value(inline, &mut args)),
+                ("--base", _) => parsed.bases.push(value(inline, &mut args)),
+                ("--merge-base-mode", _) => parsed.pairwise = pairwise(&value(inline, &mut args)),
+                ("--max-branches", _) => {
+                    let max = value(inline, &mut args).parse().ok();
+                    parsed.max_branches = Some(max.expect("--max-branches requires a number"));
+                }
+                ("--ghosts", None) => parsed.ghosts = true,
+                ("--lint", None) => parsed.lint = true,
+                ("--reviewers", None) => parsed.reviewers = true,
+                ("--label", _) => {
+                    let label = value(inline, &mut args);
+                    let (commit, note) =
+                        label.split_once('=').expect("--label requires <commit>=<text>");
+                    parsed.labels.push((commit.into(), note.into()));
+                }
+                ("--help" | "-h", None) => parsed.help = true,
+                ("--no-remotes", None) => parsed.no_remotes = true,
+                ("--remote", _) => parsed.remotes.push(value(inline, &mut args)),
+                ("--all-remotes", None) => parsed.all_remotes = true,
+                ("--remote-view", _) => parsed.remote_view = Some(value(inline, &mut args)),
+                ("--recent", _) => {
+                    parsed.recent =
+                        value(inline, &mut args).parse().expect("--recent requires a number");
+                }
+                ("--pull-requests", None) => parsed.pull_requests = true,
+                ("--refs-from-stdin", None) => parsed.refs_from_stdin = true,
+                ("--staged", None) => parsed.staged = true,
+                ("--no-stats", None) => parsed.no_stats = true,
+                ("--path", _) => parsed.paths.push(value(inline, &mut args)),
+                ("--no-merges", None) => parsed.merges = Some(Merges::Hidden),
+                ("--merges-only", None) => parsed.merges = Some(Merges::Only),
+                ("--git-dir", _) => parsed.git_dir = Some(value(inline, &mut args)),
+                ("--work-tree", _) => parsed.work_tree = Some(value(inline, &mut args)),
+                ("--repo", _) => parsed.repos.push(value(inline, &mut args)),
+                // A git log option's separate value is never a subcommand.
+                (name, None) if LOG_VALUE_OPTIONS.contains(&name) => {
+                    parsed.passthrough.push(arg);
+                    parsed.passthrough.extend(args.next());
+                }
+                // Only the first non-option argument may be a subcommand (it
+                // may still follow options, e.g. from a shell alias), so later
+                // ones, such as a branch named `status`, are revisions.
+                (name, None) if !name.starts_with('-') && !positional => {
+                    positional = true;
+                    match subcommand(name, &mut args).filter(|_| parsed.mode.is_none()) {
+                        Some(mode) => parsed.mode = Some(mode),
+                        None => parsed.passthrough.push(arg),
+                    }
+                }
+                _ => parsed.passthrough.push(arg),
+            }
+        }
+        parsed.passthrough.extend(args);
+        (parsed.negatives, parsed.positives) = split_revisions(&mut parsed.passthrough);
+        parsed
+    }
+
+    /// Returns whether the mode acts on the repository or exports from it
+    /// (e.g. `switch` or `bundle`), and so must not use a cached selection.
+    pub const fn needs_fresh_selection(&self) -> bool {
+        matches!(
+            self.mode,
+            Some(Mode::Bundle(_) | Mode::Export | Mode::Pick(_) | Mode::Select | Mode::Switch)
+        )
+    }
+
+    /// Returns whether the `git log` arguments select refs themselves (e.g.
+    /// `--all` or `--branches=feature/*`), rather than leaving the choice to
+    /// git-tree.
+    pub fn selects_refs(&self) -> bool {
+        self.passthrough.iter().take_while(|&arg| arg != "--").filter_map(|arg| arg.to_str()).any(
+            |arg| {
+                arg == "--all"
+                    || ["--branches", "--tags", "--remotes", "--glob"].iter().any(|option| {
+                        arg.strip_prefix(option)
+                            .is_some_and(|rest| rest.is_empty() || rest.starts_with('='))
+                    })
+            },
+        )
+    }
+}
+
+/// The `git log` options that may take their value as the next argument (e.g.
+/// `-n 5` or `--author ^x`), which must not be mistaken for a revision.
+const LOG_VALUE_OPTIONS: [&str; 23] = [
+    "-G",
+    "-L",
+    "-O",
+    "-S",
+    "-n",
+    "--after",
+    "--author",
+    "--before",
+    "--committer",
+    "--diff-filter",
+    "--encoding",
+    "--exclude",
+    "--find-object",
+    "--grep",
+    "--grep-reflog",
+    "--max-age",
+    "--max-count",
+    "--min-age",
+    "--since",
+    "--since-as-filter",
+    "--skip",
+    "--until",
+    "--word-diff-regex",
+];
+
+/// Removes the negative revisions (`^<rev>`, and the revisions after an odd
+/// number of --not options) from `passthrough`, returning them without their
+/// `^`, along with (copies of) the positive revisions that are not ranges. Left
+/// in place, the negative revisions would flip the sense of git-tree's own
+/// --not. The paths after `--` are left alone.
+fn split_revisions(passthrough: &mut Vec<OsString>) -> (Vec<String>, Vec<String>) {
+    let mut negatives = vec![];
+    let mut positives = vec![];
+    let mut negated = false;
+    let mut forwarded = take(passthrough).into_iter();
+    while let Some(arg) = forwarded.next() {
+        let Some(text) = arg.to_str().filter(|&text| text != "--") else {
+            let paths = arg == "--";
+            passthrough.push(arg);
+            if paths {
+                break;
+            }
+            continue;
+        };
+        if text == "--not" {
+            negated = !negated;
+            continue;
+        }
+        // An option's separate value (e.g. the pattern in `--grep ^fix`) is
+        // not a revision.
+        if LOG_VALUE_OPTIONS.contains(&text) {
+            passthrough.push(arg);
+            passthrough.extend(forwarded.next());
+            continue;
+        }
+        // Options are forwarded, but those that name refs (e.g. --all) keep
+        // their --not.
+        if text.starts_with('-') {
+            if negated {
+                passthrough.extend(["--not", text, "--not"].map(Into::into));
+            } else {
+                passthrough.push(arg);
+            }
+            continue;
+        }
+        // `^` flips the sense of a single revision.
+        match text.strip_prefix('^').map_or((text, negated), |revision| (revision, !negated)) {
+            (revision, true) => negatives.push(revision.into()),
+            (revision, false) => {
+                if !revision.contains("..") {
+                    positives.push(revision.into());
+                }
+                passthrough.push(revision.into());
+            }
+        }
+    }
+    passthrough.extend(forwarded);
+    (negatives, positives)
+}
+
+/// The remaining command-line arguments, while they are parsed.
+type Arguments = Peekable<IntoIter<OsString>>;
+
+/// Parses a subcommand's arguments.
+type Parser = fn(&mut Arguments) -> Mode;
+
+/// The subcommands: each one's usage, as --help shows it (starting with its
+/// name), and the function that parses its arguments.
+const SUBCOMMANDS: [(&str, Parser); 17] = [
+    ("ages", |_| Mode::Ages),
+    ("args", |_| Mode::Args),
+    ("bisect-hints [<bad>]", |args| {
+        // The bad ref is optional, so only take the next argument if it is not
+        // an option.
+        let bad = args
+            .next_if(|next| next.to_str().is_some_and(|next| !next.starts_with('-')))
+            .map(|bad| bad.into_string().expect("non-utf-8 argument"));
+        Mode::BisectHints(bad)
+    }),
+    ("blame-base <branch>", |args| Mode::BlameBase(required(args, "blame-base requires a branch"))),
+    ("bundle <file>", |args| Mode::Bundle(required(args, "bundle requires a file name"))),
+    ("compare <ref>", |args| {
+        Mode::Compare(required(args, "compare requires a ref to compare against"))
+    }),
+    ("diff-branches <a> <b>", |args| {
+        let message = "diff-branches requires two branches";
+        Mode::DiffBranches((required(args, message), required(args, message)))
+    }),
+    ("export --script", |args| {
+        // A shell script is the only export format so far, but the option
+        // leaves room for others.
+        let format = required(args, "export requires --script");
+        assert!(format == "--script", "export: unknown format {format}");
+        Mode::Export
+    }),
+    ("is-ancestor", |_| Mode::IsAncestor),
+    ("pick [<n> [<command>...]]", |args| {
+        // Everything after the number is the command to run, even if it looks
+        // like one of git-tree's options.
+        let number = args.next().map(|number| {
+            number
+                .to_str()
+                .and_then(|number| number.parse().ok())
+                .expect("pick requires a branch number")
+        });
+        Mode::Pick(number.map(|number| (number, args.by_ref().collect())))
+    }),
+    ("search <regex>", |args| Mode::Search(required(args, "search requires a regular expression"))),
+    ("select", |_| Mode::Select),
+    ("stats", |_| Mode::Stats),
+    ("status", |_| Mode::Status),
+    ("switch", |_| Mode::Switch),
+    ("timeline", |_| Mode::Timeline),
+    ("undo-info <branch>", |args| Mode::UndoInfo(required(args, "undo-info requires a branch"))),
+];
+
+/// Returns whether `name` is a subcommand.
+pub fn is_subcommand(name: &str) -> bool {
+    SUBCOMMANDS.iter().any(|subcommand| subcommand_name(subcommand.0) == name)
+}
+
+/// Returns the name of the subcommand with this `usage`.
+fn subcommand_name(usage: &str) -> &str {
+    usage.split_once(' ').map_or(usage, |(name, _)| name)
+}
+
+/// Parses `name` as a subcommand, taking the subcommand's arguments from
+/// `args`. Returns `None` if `name` is not a subcommand.
+fn subcommand(name: &str, args: &mut Arguments) -> Option<Mode> {
+    let found = SUBCOMMANDS.iter().find(|subcommand| subcommand_name(subcommand.0) == name);
+    found.map(|subcommand| subcommand.1(args))
+}
+
+/// Returns the text --help prints, with the subcommands' usages listed in lines
+/// of at most 80 columns.
+pub fn help() -> String {
+    let mut text = HELP.to_owned();
+    let mut line = String::from(" ");
+    for (idx, subcommand) in SUBCOMMANDS.iter().enumerate() {
+        let last = idx.saturating_add(1) == SUBCOMMANDS.len();
+        let item = if last { subcommand.0.to_owned() } else { format!("{},", subcommand.0) };
+        if line.len().saturating_add(item.len()) >= 80 {
+            text.push_str(&line);
+            text.push('\n');
+            line = " ".into();
+        }
+        line.push(' ');
+        line.push_str(&item);
+    }
+    text.push_str(&line);
+    text.push('\n');
+    text.push_str(HELP_SETTINGS);
+    text
+}
+
+/// Returns a subcommand's next argument, which it requires. `message`
+/// describes the missing argument.
+fn required<I: Iterator<Item = OsString>>(args: &mut I, message: &str) -> String {
+    args.next().expect(message).into_string().expect("non-utf-8 argument")
+}
+
+/// Parses the value of --mode.
+fn profile(text: &str) -> Profile {
+    let profile = match text {
+        "minimal" => Some(Profile::Minimal),
+        "default" => Some(Profile::Default),
+        "full" => Some(Profile::Full),
+        _ => None,
+    };
+    assert!(profile.is_some(), "invalid --mode {text} (expected minimal, default, or full)");
+    profile.unwrap()
+}
+
+/// Parses the value of --merge-base-mode, returning whether it is `pairwise`.
+fn pairwise(mode: &str) -> bool {
+    assert!(
+        matches!(mode, "octopus" | "pairwise"),
+        "invalid --merge-base-mode {mode} (expected octopus or pairwise)"
+    );
+    mode == "pairwise"
+}
+
+/// Parses a duration such as `30d` (for --branch-since) into seconds. The
+/// units are `h` (hours), `d` (days), `w` (weeks), and `y` (365 days).
+fn duration(text: &str) -> u64 {
+    let unit: u64 = match text.chars().last() {
+        Some('h') => 60 * 60,
+        Some('d') => 24 * 60 * 60,
+        Some('w') => 7 * 24 * 60 * 60,
+        Some('y') => 365 * 24 * 60 * 60,
+        _ => 0,
+    };
+    let count =
+        text.get(..text.len().saturating_sub(1)).and_then(|count| count.parse::<u64>().ok());
+    assert!(unit != 0 && count.is_some(), "invalid duration {text} (expected e.g. 30d)");
+    count.unwrap().saturating_mul(unit)
+}
+
+/// Returns an option's value, which is either `inline` (from `--name=value`)
+/// or the next argument.
+fn value<I: Iterator<Item = OsString>>(inline: Option<&str>, args: &mut I) -> String {
+    inline.map_or_else(
+        || {
+            args.next()
+                .expect("missing option value")
+                .into_string()
+                .expect("non-utf-8 option value")
+        },
+        Into::into,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{help, is_subcommand, split_revisions, Args, Mode, SUBCOMMANDS};
+    use std::ffi::OsString;
+
+    /// Parses `args` as git-tree's command line.
+    fn parse(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(OsString::from).collect())
+    }
+
+    #[test]
+    fn parse_recognizes_subcommands_after_options() {
+        let args = parse(&["--oneline", "stats"]);
+        assert!(matches!(args.mode, Some(Mode::Stats)));
+        assert_eq!(args.passthrough, ["--oneline"]);
+    }
+
+    #[test]
+    fn parse_only_recognizes_the_first_word_as_a_subcommand() {
+        let revisions = parse(&["main", "status"]);
+        assert!(revisions.mode.is_none());
+        assert_eq!(revisions.positives, ["main", "status"]);
+        let after_value = parse(&["--grep", "status", "args"]);
+        assert!(matches!(after_value.mode, Some(Mode::Args)));
+        assert_eq!(after_value.passthrough, ["--grep", "status"]);
+        let formatted = parse(&["--format=markdown", "stats"]);
+        assert!(matches!(formatted.mode, Some(Mode::Markdown)));
+        assert_eq!(formatted.positives, ["stats"]);
+    }
+
+    #[test]
+    fn parse_takes_subcommand_arguments() {
+        let diff = parse(&["diff-branches", "a", "b", "--oneline"]);
+        assert!(matches!(diff.mode, Some(Mode::DiffBranches((a, b))) if a == "a" && b == "b"));
+        assert_eq!(diff.passthrough, ["--oneline"]);
+        let pick = parse(&["pick", "2", "git", "show", "--stat"]);
+        assert!(matches!(
+            pick.mode,
+            Some(Mode::Pick(Some((2, command)))) if command == ["git", "show", "--stat"]
+        ));
+    }
+
+    #[test]
+    fn parse_reads_options() {
+        let args = parse(&["--exclude-branch", "wip/*", "--remote=origin", "--branch-since=2d"]);
+        assert_eq!(args.exclude_branches, ["wip/*"]);
+        assert_eq!(args.remotes, ["origin"]);
+        assert_eq!(args.branch_since, Some(2 * 24 * 60 * 60));
+        assert_eq!(parse(&[]).recent, 50);
+        assert!(parse(&["--merge-base-mode", "pairwise"]).pairwise);
+    }
+
+    #[test]
+    fn help_lists_every_subcommand() {
+        let text = help();
+        assert!(text.lines().all(|line| line.len() <= 80));
+        for (usage, _) in SUBCOMMANDS {
+            assert!(text.contains(usage));
+        }
+        assert!(is_subcommand("undo-info"));
+        assert!(!is_subcommand("undo-info <branch>"));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid --mode")]
+    fn parse_rejects_unknown_modes() {
+        parse(&["--mode=everything"]);
+    }
+
+    /// Runs `split_revisions` on `args`, returning the remaining arguments
+    /// along with the negative and positive revisions.
+    fn split(args: &[&str]) -> (Vec<OsString>, Vec<String>, Vec<String>) {
+        let mut passthrough = args.iter().map(OsString::from).collect();
+        let (negatives, positives) = split_revisions(&mut passthrough);
+        (passthrough, negatives, positives)
+    }
+
+    #[test]
+    fn split_revisions_classifies_revisions() {
+        let (passthrough, negatives, positives) = split(&["main", "^old", "a..b", "--oneline"]);
+        assert_eq!(passthrough, ["main", "a..b", "--oneline"]);
+        assert_eq!(negatives, ["old"]);
+        assert_eq!(positives, ["main"]);
+    }
+
+    #[test]
+    fn split_revisions_handles_not() {
+        let (passthrough, negatives, positives) =
+            split(&["--not", "old", "^new", "--all", "--not", "main"]);
+        assert_eq!(passthrough, ["new", "--not", "--all", "--not", "main"]);
+        assert_eq!(negatives, ["old"]);
+        assert_eq!(positives, ["new", "main"]);
+    }
+
+    #[test]
+    fn split_revisions_skips_option_values() {
+        let args = ["--grep", "^f", "--author", "^x", "-S", "^y", "-n", "5", "-G^z"];
+        let (passthrough, negatives, positives) = split(&args);
+        assert_eq!(passthrough, args);
+        assert!(negatives.is_empty());
+        assert!(positives.is_empty());
+    }
+
+    #[test]
+    fn split_revisions_leaves_paths() {
+        let (passthrough, negatives, positives) = split(&["^old", "--", "^path", "main"]);
+        assert_eq!(passthrough, ["--", "^path", "main"]);
+        assert_eq!(negatives, ["old"]);
+        assert!(positives.is_empty());
+    }
+}
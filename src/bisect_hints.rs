@@ -0,0 +1,59 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use std::io::{stdout, Write as _};
+
+/// Implements `git-tree bisect-hints [<bad>]`, which suggests a `git bisect
+/// start` command that uses the merge bases below `bad` (default: HEAD) as the
+/// known-good commits.
+pub fn bisect_hints(git: &GitRunner, selection: &Selection, bad: Option<&str>) {
+    let bad = bad.unwrap_or("HEAD");
+    let good: Vec<_> = selection
+        .merge_bases
+        .iter()
+        .filter(|base| git.query("merge-base", &["--is-ancestor", base, bad]).is_some())
+        .map(String::as_str)
+        .collect();
+    let mut out = stdout().lock();
+    if good.is_empty() {
+        writeln!(out, "# No merge base is an ancestor of {bad}; there is no range to suggest.")
+            .written();
+        return;
+    }
+    let mut count_args = vec!["--count", bad, "--not"];
+    count_args.extend(&good);
+    count_args.push("--");
+    count_args.extend(selection.scope.iter().map(String::as_str));
+    let count: u64 = git
+        .query("rev-list", &count_args)
+        .and_then(|count| count.parse().ok())
+        .expect("git rev-list --count failed");
+    // Bisection tests about log2(count + 1) commits.
+    let steps = u64::BITS.saturating_sub(count.leading_zeros());
+    writeln!(
+        out,
+        "# {count} commit{} between the merge bases and {bad} (about {steps} step{})",
+        if count == 1 { "" } else { "s" },
+        if steps == 1 { "" } else { "s" },
+    )
+    .written();
+    write!(out, "git bisect start {bad} {}", good.join(" ")).written();
+    if !selection.scope.is_empty() {
+        write!(out, " -- {}", selection.scope.join(" ")).written();
+    }
+    writeln!(out).written();
+}
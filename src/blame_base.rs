@@ -0,0 +1,95 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use core::iter::once;
+use std::io::{stdout, Write as _};
+
+/// Implements `git-tree blame-base <branch>`, which explains where the history
+/// below `branch` is cut off: it prints the merge bases, then each of the other
+/// interesting commits that holds them down, with where the merge bases would
+/// be without it. The commit that moves them furthest is listed first, so a
+/// stale branch responsible for a huge tree stands out.
+pub fn blame_base(git: &GitRunner, selection: &Selection, branch: &str) {
+    assert!(
+        git.query("rev-parse", &["--verify", "--quiet", &format!("{branch}^{{commit}}")]).is_some(),
+        "{branch} is not a commit"
+    );
+    let mut refs: Vec<&str> =
+        once("HEAD").chain(selection.interesting_branches.iter().map(String::as_str)).collect();
+    refs.push(branch);
+    refs.dedup();
+    let current = merge_bases(git, &refs);
+    let mut out = stdout().lock();
+    writeln!(out, "{branch} is cut off at {}", describe(git, &current)).written();
+    let mut others: Vec<_> = refs
+        .iter()
+        .copied()
+        .filter(|&other| other != branch)
+        .filter_map(|other| {
+            let without: Vec<_> = refs.iter().copied().filter(|&name| name != other).collect();
+            let bases = merge_bases(git, &without);
+            if bases == current {
+                return None;
+            }
+            // The commits between the current merge bases and the new ones.
+            let args: Vec<_> = once("--count")
+                .chain(bases.iter().map(String::as_str))
+                .chain(once("--not"))
+                .chain(current.iter().map(String::as_str))
+                .chain(once("--"))
+                .collect();
+            let count: usize = git
+                .query("rev-list", &args)
+                .and_then(|count| count.parse().ok())
+                .expect("git rev-list --count failed");
+            Some((other, bases, count))
+        })
+        .collect();
+    if others.is_empty() {
+        writeln!(out, "No single interesting commit holds it there; several share that base.")
+            .written();
+        return;
+    }
+    others.sort_by_key(|other| usize::MAX.saturating_sub(other.2));
+    writeln!(out, "It is held there by:").written();
+    for (other, bases, count) in others {
+        writeln!(out, "  {other}: without it, {} ({count} commits higher)", describe(git, &bases))
+            .written();
+    }
+}
+
+/// Returns the merge bases of `refs`, as git-tree computes them.
+fn merge_bases(git: &GitRunner, refs: &[&str]) -> Vec<String> {
+    let args: Vec<_> = ["-a", "--octopus"].into_iter().chain(refs.iter().copied()).collect();
+    let bases = git.query("merge-base", &args).unwrap_or_default();
+    let mut bases: Vec<String> = bases.lines().map(Into::into).collect();
+    bases.sort_unstable();
+    bases
+}
+
+/// Describes `bases` by their abbreviated IDs and subjects.
+fn describe(git: &GitRunner, bases: &[String]) -> String {
+    if bases.is_empty() {
+        return "no merge base (the history is unrelated)".into();
+    }
+    let args: Vec<_> = ["--no-walk", "--format=%h %s"]
+        .into_iter()
+        .chain(bases.iter().map(String::as_str))
+        .chain(once("--"))
+        .collect();
+    git.query("log", &args).unwrap_or_default().lines().collect::<Vec<_>>().join(", ")
+}
@@ -0,0 +1,100 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::selection::Selection;
+use crate::trunk::default_branch;
+use std::collections::HashMap;
+
+/// Returns a map from interesting branch to a description of how far it is
+/// ahead of and behind its upstream (e.g. `ahead 2, behind 1 of origin/x`),
+/// and, if `tree.stats` is `all`, the default branch too. `tree.stats` defaults
+/// to `upstream`. Setting it to `off`, or passing --no-stats (`disabled`), skips
+/// these computations, which can be slow on repositories with many branches.
+pub fn branch_stats(
+    git: &GitRunner,
+    selection: &Selection,
+    disabled: bool,
+) -> HashMap<String, String> {
+    let level = git.settings().get("tree.stats").unwrap_or("upstream");
+    assert!(matches!(level, "off" | "upstream" | "all"), "invalid tree.stats {level}");
+    if disabled || level == "off" {
+        return HashMap::new();
+    }
+    let mut stats: HashMap<String, Vec<String>> = HashMap::new();
+    // Branches whose upstream is the default branch already show how far
+    // they are from it.
+    let mut upstreams = HashMap::new();
+    // git computes the upstream counts itself, in one pass.
+    let listing = git
+        .query(
+            "for-each-ref",
+            &[
+                "--format=%(refname:short)%00%(upstream:short)%00%(upstream:track,nobracket)",
+                "refs/heads",
+            ],
+        )
+        .unwrap_or_default();
+    for line in listing.lines() {
+        let mut fields = line.split('\0');
+        let (Some(branch), Some(upstream), Some(track)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if upstream.is_empty() || !selection.interesting_branches.iter().any(|name| name == branch)
+        {
+            continue;
+        }
+        let text = match track {
+            "" => format!("up to date with {upstream}"),
+            "gone" => format!("{upstream} is gone"),
+            counts => format!("{counts} of {upstream}"),
+        };
+        stats.entry(branch.into()).or_default().push(text);
+        upstreams.insert(branch, upstream);
+    }
+    if level == "all" {
+        if let Some(trunk) = default_branch(git) {
+            let tracks_trunk = |branch: &str| upstreams.get(branch) == Some(&trunk.as_str());
+            for branch in selection
+                .interesting_branches
+                .iter()
+                .filter(|&branch| *branch != trunk && !tracks_trunk(branch))
+            {
+                stats.entry(branch.clone()).or_default().push(compared(git, branch, &trunk));
+            }
+        }
+    }
+    stats.into_iter().map(|(branch, texts)| (branch, texts.join("; "))).collect()
+}
+
+/// Describes how far `branch` is ahead of and behind `trunk`, in the style of
+/// `%(upstream:track)`.
+fn compared(git: &GitRunner, branch: &str, trunk: &str) -> String {
+    let counts = git
+        .query("rev-list", &["--left-right", "--count", &format!("{trunk}...{branch}"), "--"])
+        .expect("git rev-list --left-right --count failed");
+    let (behind, ahead) = counts.split_once('\t').expect("invalid rev-list output");
+    let nonzero: Vec<_> = [("ahead", ahead), ("behind", behind)]
+        .into_iter()
+        .filter(|count| count.1 != "0")
+        .map(|count| format!("{} {}", count.0, count.1))
+        .collect();
+    if nonzero.is_empty() {
+        format!("up to date with {trunk}")
+    } else {
+        format!("{} of {trunk}", nonzero.join(", "))
+    }
+}
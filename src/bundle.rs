@@ -0,0 +1,43 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::selection::Selection;
+use std::collections::HashMap;
+
+/// Implements `git-tree bundle <file>`, which writes the interesting branches
+/// (and HEAD) to a git bundle containing the commits beyond the merge bases.
+/// The merge bases are the bundle's prerequisites, so it can be unbundled in
+/// any clone that has them. Interesting branches that are not refs, such as
+/// `stash@{1}` and other worktrees' HEADs, are left out, as a bundle can only
+/// hold refs.
+pub fn bundle(git: &GitRunner, selection: &Selection, file: &str) {
+    let listing =
+        git.query("for-each-ref", &["--format=%(refname:short)%00%(refname)"]).unwrap_or_default();
+    let refnames: HashMap<_, _> = listing
+        .lines()
+        .filter_map(|line| line.split_once('\0'))
+        .flat_map(|(short, full)| [(short, full), (full, full)])
+        .collect();
+    let refs = selection.interesting_branches.iter().filter_map(|name| refnames.get(name.as_str()));
+    let status = git
+        .command("bundle")
+        .args(["create", file, "HEAD"])
+        .args(refs)
+        .arg("--not")
+        .args(&selection.merge_bases)
+        .status()
+        .expect("failed to run git");
+    assert!(status.success(), "git returned unsuccessful status {status}");
+}
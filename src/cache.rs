@@ -0,0 +1,150 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commits::Merges;
+use crate::git_runner::GitRunner;
+use crate::selection::Selection;
+use core::hash::{Hash as _, Hasher as _};
+use core::time::Duration;
+use std::env::{args_os, current_exe};
+use std::fs::{create_dir_all, read_to_string, remove_file, rename, write, OpenOptions};
+use std::hash::DefaultHasher;
+use std::path::{Path, PathBuf};
+use std::process::{id as process_id, Command, Stdio};
+use std::time::SystemTime;
+
+/// Environment variable that makes git-tree refresh the cache and exit, set on
+/// the child that `selection` spawns.
+pub const REFRESH: &str = "GIT_TREE_REFRESH_CACHE";
+
+/// How old a lock file must be before it is assumed to belong to a refresh
+/// that died.
+const STALE_LOCK: Duration = Duration::from_secs(10 * 60);
+
+/// Returns the selection, from the cache under `.git/git-tree/` if
+/// `tree.backgroundCache` is set and the cache was written for the same `key`
+/// (the command line) and the same `tree.*` settings. The cached selection is
+/// returned at once even though it may be out of date (a fetch or commit may
+/// have moved the refs since), and a detached copy of git-tree is started to
+/// refresh it for the next run. Without a usable cache, the selection is
+/// computed (and, if the setting is on, stored).
+pub fn selection<F: FnOnce() -> Selection>(git: &GitRunner, key: &str, compute: F) -> Selection {
+    if git.settings().get_bool("tree.backgroundCache") != Some(true) {
+        return compute();
+    }
+    let dir = directory(git);
+    let key = format!("{key} {}", fingerprint(git));
+    if let Some(selection) = load(&dir, &key) {
+        // The child only writes the cache, so its output is discarded, and it
+        // is not waited for.
+        #[allow(clippy::zombie_processes, reason = "the refresh outlives this run")]
+        Command::new(current_exe().expect("cannot locate git-tree"))
+            .args(args_os().skip(1))
+            .env(REFRESH, "1")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start the cache refresh");
+        return selection;
+    }
+    let selection = compute();
+    store(&dir, &key, &selection);
+    selection
+}
+
+/// Recomputes the selection and stores it in the cache, unless another refresh
+/// holds the lock. Run by the child `selection` spawns.
+pub fn refresh<F: FnOnce() -> Selection>(git: &GitRunner, key: &str, compute: F) {
+    let dir = directory(git);
+    let lock = dir.join("lock");
+    let stale = lock
+        .metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > STALE_LOCK);
+    if stale {
+        remove_file(&lock).expect("failed to remove stale cache lock");
+    }
+    if OpenOptions::new().write(true).create_new(true).open(&lock).is_err() {
+        return;
+    }
+    store(&dir, &format!("{key} {}", fingerprint(git)), &compute());
+    remove_file(&lock).expect("failed to remove cache lock");
+}
+
+/// Returns a hash of the `tree.*` settings, so a cached selection is not used
+/// once they have been edited.
+fn fingerprint(git: &GitRunner) -> String {
+    let mut hasher = DefaultHasher::new();
+    git.settings().hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Returns the cache directory, creating it if necessary.
+fn directory(git: &GitRunner) -> PathBuf {
+    let git_dir = git.query("rev-parse", &["--absolute-git-dir"]);
+    assert!(git_dir.is_some(), "tree.backgroundCache: not in a git repository");
+    let dir = PathBuf::from(git_dir.unwrap()).join("git-tree");
+    create_dir_all(&dir).expect("failed to create the cache directory");
+    dir
+}
+
+/// Reads the cached selection, if it was stored for `key`. The file has a line
+/// for the key followed by a line for each of the selection's lists, whose
+/// items (refs, commit IDs, and pathspecs) are separated by NULs.
+fn load(dir: &Path, key: &str) -> Option<Selection> {
+    let contents = read_to_string(dir.join("selection")).unwrap_or_default();
+    let mut lines = contents.lines();
+    if lines.next() != Some(key) {
+        return None;
+    }
+    let mut list = || {
+        lines
+            .next()
+            .map(|line| line.split('\0').filter(|item| !item.is_empty()).map(Into::into).collect())
+    };
+    let (branches, merge_bases, includes, excludes, scope) =
+        (list(), list(), list(), list(), list());
+    scope.map(|scope| Selection {
+        interesting_branches: branches.unwrap_or_default(),
+        merge_bases: merge_bases.unwrap_or_default(),
+        includes: includes.unwrap_or_default(),
+        excludes: excludes.unwrap_or_default(),
+        scope,
+        paths: vec![],
+        merges: Merges::Shown,
+    })
+}
+
+/// Writes `selection` to the cache for `key`. The file is replaced atomically,
+/// so a concurrent run never reads a partial cache.
+fn store(dir: &Path, key: &str, selection: &Selection) {
+    let lists = [
+        &selection.interesting_branches,
+        &selection.merge_bases,
+        &selection.includes,
+        &selection.excludes,
+        &selection.scope,
+    ];
+    let mut contents = format!("{key}\n");
+    for list in lists {
+        contents.push_str(&list.join("\0"));
+        contents.push('\n');
+    }
+    let temporary = dir.join(format!("selection.{}", process_id()));
+    write(&temporary, contents).expect("failed to write the cache");
+    rename(&temporary, dir.join("selection")).expect("failed to write the cache");
+}
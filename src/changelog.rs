@@ -0,0 +1,90 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::annotations::Annotations;
+use crate::commits::{outstanding_commits, Commit};
+use crate::conventional;
+use crate::git_runner::GitRunner;
+use crate::markdown::write_containment;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use std::io::{stdout, StdoutLock, Write as _};
+
+/// Implements `--changelog`, which prints the commits between each branch's
+/// merge base and its tip as Markdown. If `branch` is specified, only that
+/// branch is included. If `tree.conventional` is set, each branch's commits
+/// are grouped by their Conventional Commits type.
+/// Precondition: `buffer` must be empty.
+pub fn changelog(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    selection: &Selection,
+    annotations: &Annotations,
+    branch: Option<&str>,
+) {
+    let mut branches: Vec<_> = selection.interesting_branches.iter().enumerate().collect();
+    if let Some(branch) = branch {
+        branches.retain(|&(_, name)| name == branch);
+        assert!(!branches.is_empty(), "{branch} is not an interesting branch");
+    }
+    let commits = outstanding_commits(git, buffer, selection);
+    let conventional = conventional::enabled(git);
+    let mut out = stdout().lock();
+    let mut first = true;
+    for (idx, name) in branches {
+        let commits: Vec<_> =
+            commits.iter().filter(|commit| commit.branches.binary_search(&idx).is_ok()).collect();
+        if commits.is_empty() {
+            continue;
+        }
+        if !first {
+            writeln!(out).written();
+        }
+        first = false;
+        writeln!(out, "## {name}").written();
+        if !conventional {
+            writeln!(out).written();
+            write_commits(&mut out, annotations, commits.iter().copied());
+            continue;
+        }
+        let mut types: Vec<_> = commits
+            .iter()
+            .map(|commit| (conventional::commit_type(&commit.subject), *commit))
+            .collect();
+        // None sorts first, but commits without a type belong at the end.
+        types.sort_by_key(|&(ty, _)| ty.unwrap_or(conventional::TYPES.len()));
+        for group in types.chunk_by(|a, b| a.0 == b.0) {
+            let heading = group
+                .first()
+                .unwrap()
+                .0
+                .map_or(conventional::OTHER, |ty| conventional::TYPES.get(ty).unwrap().1);
+            writeln!(out, "\n### {heading}\n").written();
+            write_commits(&mut out, annotations, group.iter().map(|&(_, commit)| commit));
+        }
+    }
+}
+
+/// Writes a Markdown list item for each commit.
+fn write_commits<'commit, I: Iterator<Item = &'commit Commit>>(
+    out: &mut StdoutLock,
+    annotations: &Annotations,
+    commits: I,
+) {
+    for commit in commits {
+        write!(out, "- {} ({})", commit.title, commit.short_id).written();
+        write_containment(out, annotations, commit);
+        writeln!(out).written();
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::selection::Selection;
+use core::iter::once;
+use std::collections::HashMap;
+
+/// Where GitHub looks for the CODEOWNERS file, in the order it looks.
+const LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Implements --reviewers: returns a map from each interesting branch to the
+/// owners (per the CODEOWNERS file at HEAD) of the paths its outstanding
+/// commits touch, sorted. Branches without owners are left out.
+pub fn reviewers(git: &GitRunner, selection: &Selection) -> HashMap<String, Vec<String>> {
+    let Some(file) =
+        LOCATIONS.iter().find_map(|path| git.query("cat-file", &["blob", &format!("HEAD:{path}")]))
+    else {
+        return HashMap::new();
+    };
+    let rules = parse(&file);
+    let negative: Vec<_> = once("--not")
+        .chain(selection.merge_bases.iter().map(String::as_str))
+        .chain(selection.excludes.iter().map(String::as_str))
+        .collect();
+    let mut reviewers = HashMap::new();
+    for branch in &selection.interesting_branches {
+        let args: Vec<_> = ["--format=", "--name-only", "--no-renames", branch.as_str()]
+            .into_iter()
+            .chain(negative.iter().copied())
+            .chain(once("--"))
+            .collect();
+        let touched = git.query("log", &args).unwrap_or_default();
+        let mut owners: Vec<String> = touched
+            .lines()
+            .filter(|path| !path.is_empty())
+            .flat_map(|path| owners_of(&rules, path))
+            .map(Into::into)
+            .collect();
+        owners.sort_unstable();
+        owners.dedup();
+        if !owners.is_empty() {
+            reviewers.insert(branch.clone(), owners);
+        }
+    }
+    reviewers
+}
+
+/// A CODEOWNERS line: a pattern and the owners of the paths it matches.
+struct Rule<'file> {
+    pattern: &'file str,
+    owners: Vec<&'file str>,
+}
+
+/// Parses a CODEOWNERS file, skipping blank lines and comments.
+fn parse(file: &str) -> Vec<Rule<'_>> {
+    file.lines()
+        .map(|line| line.split_once('#').map_or(line, |(rule, _)| rule))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            fields.next().map(|pattern| Rule { pattern, owners: fields.collect() })
+        })
+        .collect()
+}
+
+/// Returns the owners of `path`: those of the last rule that matches it. A rule
+/// without owners leaves the path unowned.
+fn owners_of<'file>(rules: &[Rule<'file>], path: &str) -> Vec<&'file str> {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| pattern_matches(rule.pattern, path))
+        .map(|rule| rule.owners.clone())
+        .unwrap_or_default()
+}
+
+/// Returns whether the CODEOWNERS `pattern` matches `path`. As in gitignore, a
+/// pattern matches the files below the directories it matches, and a pattern
+/// without a slash (other than a trailing one) matches at any depth. As on
+/// GitHub, a pattern whose last component has a `*` (e.g. `docs/*`) matches
+/// files rather than directories, so only the direct children of `docs/`.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let anchored = pattern.trim_end_matches('/').contains('/');
+    let glob = pattern.trim_start_matches('/').trim_end_matches('/');
+    let files_only = glob.rsplit('/').next().is_some_and(|last| last.contains('*'));
+    let glob = glob.as_bytes();
+    let slashes: Vec<_> = path.match_indices('/').map(|slash| slash.0).collect();
+    // The matched part of the path must start and end at a component boundary.
+    let starts =
+        once(0).chain(slashes.iter().filter(|_| !anchored).map(|&idx| idx.saturating_add(1)));
+    let ends: Vec<_> =
+        slashes.iter().copied().filter(|_| !files_only).chain(once(path.len())).collect();
+    starts.into_iter().any(|start| {
+        ends.iter()
+            .filter_map(|&end| path.get(start..end))
+            .any(|part| path_glob_matches(glob, part.as_bytes()))
+    })
+}
+
+/// Returns whether `text` matches the glob `pattern`, in which `**` matches
+/// any sequence of characters (and `**/` any number of directories), while `*`
+/// matches any sequence without a `/` and `?` any one character but `/`.
+fn path_glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    if let Some(rest) = pattern.strip_prefix(b"**") {
+        return (0..=text.len()).any(|skip| path_glob_matches(rest, text.get(skip..).unwrap()))
+            || rest.strip_prefix(b"/").is_some_and(|rest| path_glob_matches(rest, text));
+    }
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((&b'*', rest)) => {
+            let component = text.iter().position(|&b| b == b'/').unwrap_or(text.len());
+            (0..=component).any(|skip| path_glob_matches(rest, text.get(skip..).unwrap()))
+        }
+        Some((&c, rest)) => text.split_first().is_some_and(|(&t, text)| {
+            (c == t || c == b'?' && t != b'/') && path_glob_matches(rest, text)
+        }),
+    }
+}
@@ -0,0 +1,542 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::{read_line, GitRunner};
+use crate::selection::Selection;
+use core::str;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::io::BufReader;
+use std::process::{Command, Stdio};
+
+/// Which commits native output shows, by whether they are merges (from
+/// --no-merges, --merges-only, and `tree.hideMerges`).
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Merges {
+    #[default]
+    Shown,
+    Hidden,
+    Only,
+}
+
+impl Merges {
+    /// The equivalent `git log` option, if any.
+    pub const fn log_option(self) -> Option<&'static str> {
+        match self {
+            Self::Shown => None,
+            Self::Hidden => Some("--no-merges"),
+            Self::Only => Some("--merges"),
+        }
+    }
+}
+
+/// An outstanding commit, as loaded by `outstanding_commits`.
+pub struct Commit {
+    pub id: String,
+    pub short_id: String,
+
+    /// Parent IDs, including parents that were not loaded.
+    pub parents: Vec<String>,
+
+    /// `Name <email>`.
+    pub author: String,
+
+    /// Author date, in seconds since the Unix epoch.
+    pub timestamp: i64,
+
+    /// Author date, formatted for display.
+    pub date: String,
+
+    pub subject: String,
+
+    /// The commit's description in native output formats: `subject`, or the
+    /// commit formatted with `tree.pretty`.
+    pub title: String,
+
+    /// Indexes into `Selection::interesting_branches` of the branches that
+    /// contain this commit, sorted.
+    pub branches: Vec<usize>,
+}
+
+/// Returns the outstanding commits (the displayed commits other than the merge
+/// bases), children before parents.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn outstanding_commits(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    selection: &Selection,
+) -> Vec<Commit> {
+    let tips = branch_tips(git, buffer, &selection.interesting_branches);
+    let anchors = anchors(git, selection, &tips);
+    let mut command = git.internal("log");
+    command.arg("--topo-order").args(selection.outstanding_revisions());
+    let commits = read_commits(git, command, buffer, tips);
+    let kept = kept_merges(selection.merges, &anchors, &commits);
+    let commits =
+        apply_paths(git, &selection.paths, selection.outstanding_revisions(), &anchors, commits);
+    let commits =
+        apply_scope(git, buffer, &selection.scope, selection.outstanding_revisions(), commits);
+    apply_merges(kept, commits)
+}
+
+/// Returns the displayed commits (the outstanding commits plus the merge
+/// bases), children before parents.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn displayed_commits(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    selection: &Selection,
+) -> Vec<Commit> {
+    let tips = branch_tips(git, buffer, &selection.interesting_branches);
+    let anchors = anchors(git, selection, &tips);
+    let mut command = git.internal("log");
+    command.arg("--topo-order").args(selection.revisions());
+    let commits = read_commits(git, command, buffer, tips);
+    let kept = kept_merges(selection.merges, &anchors, &commits);
+    let commits = apply_paths(git, &selection.paths, selection.revisions(), &anchors, commits);
+    let commits = apply_scope(git, buffer, &selection.scope, selection.revisions(), commits);
+    apply_merges(kept, commits)
+}
+
+/// Returns the merge bases, in the same order as `Selection::merge_bases`.
+/// Their `branches` lists are empty.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn merge_base_commits(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    selection: &Selection,
+) -> Vec<Commit> {
+    if selection.merge_bases.is_empty() {
+        return vec![];
+    }
+    let mut command = git.internal("log");
+    command.arg("--no-walk=unsorted").args(&selection.merge_bases);
+    read_commits(git, command, buffer, HashMap::new())
+}
+
+/// Returns the commits reachable from `ghosts` (old branch tips) that are not
+/// displayed, children before parents. Their `branches` lists are empty.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn ghost_commits(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    selection: &Selection,
+    ghosts: &[&str],
+) -> Vec<Commit> {
+    if ghosts.is_empty() {
+        return vec![];
+    }
+    let mut command = git.internal("log");
+    command
+        .arg("--topo-order")
+        .args(ghosts)
+        .arg("--not")
+        .args(&selection.includes)
+        .args(&selection.merge_bases);
+    read_commits(git, command, buffer, HashMap::new())
+}
+
+/// Returns the `--date` option that formats `Commit::date`, per
+/// `tree.dateFormat`. The setting is one of git's date formats (e.g. `relative`,
+/// `iso`, or `short`, the default) or a strftime pattern.
+pub fn date_option(git: &GitRunner) -> String {
+    match git.settings().get("tree.dateFormat") {
+        None => "--date=short".into(),
+        // git's named formats never contain %, so this is a bare pattern.
+        Some(format) if format.contains('%') && !format.starts_with("format") => {
+            format!("--date=format:{format}")
+        }
+        Some(format) => format!("--date={format}"),
+    }
+}
+
+/// Calls `visit` with each displayed commit, children before parents. Unless
+/// the commits are filtered by path or by whether they are merges (which needs
+/// the whole graph), each commit is visited as soon as git prints it.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn stream_displayed_commits<F: FnMut(Commit)>(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    selection: &Selection,
+    visit: F,
+) {
+    if !selection.paths.is_empty()
+        || !selection.scope.is_empty()
+        || selection.merges != Merges::Shown
+    {
+        return displayed_commits(git, buffer, selection).into_iter().for_each(visit);
+    }
+    let tips = branch_tips(git, buffer, &selection.interesting_branches);
+    let mut command = git.internal("log");
+    command.arg("--topo-order").args(selection.revisions());
+    for_each_commit(git, command, buffer, tips, visit);
+}
+
+/// Runs `command` (a `git log` invocation missing its format and paths) and
+/// parses its output. `tips` maps commit IDs to the indexes of the branches
+/// that point to them, and is used to compute `Commit::branches`.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+fn read_commits(
+    git: &GitRunner,
+    command: Command,
+    buffer: &mut Vec<u8>,
+    tips: HashMap<String, Vec<usize>>,
+) -> Vec<Commit> {
+    let mut commits = vec![];
+    for_each_commit(git, command, buffer, tips, |commit| commits.push(commit));
+    commits
+}
+
+/// Like `read_commits`, but calls `visit` with each commit as it is parsed.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+fn for_each_commit<F: FnMut(Commit)>(
+    git: &GitRunner,
+    mut command: Command,
+    buffer: &mut Vec<u8>,
+    mut tips: HashMap<String, Vec<usize>>,
+    mut visit: F,
+) {
+    // The title, if `tree.pretty` gives it a format, goes last so that any
+    // NULs it contains only split the title itself.
+    let title_format = title_format(git);
+    let mut format = "--format=%H%x00%h%x00%P%x00%aN <%aE>%x00%at%x00%ad%x00%s".to_owned();
+    if let Some(title) = title_format.as_deref() {
+        format.push_str("%x00");
+        format.push_str(title);
+    }
+    let mut git = command
+        .arg(date_option(git))
+        .arg(format)
+        .arg("--")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run git");
+    // Branches that reach a commit we have not visited yet through one of its
+    // (already visited) children. Because git prints children before their
+    // parents, a commit's entry is complete by the time we visit it.
+    let mut inherited: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut reader = BufReader::new(git.stdout.as_mut().unwrap());
+    while let Some(len) = read_line(&mut reader, buffer) {
+        let mut fields = buffer.get(..len).unwrap().split(|&b| b == 0);
+        let mut field = || fields.next().expect("truncated git log output");
+        let id = String::from_utf8(field().to_vec()).expect("non-utf-8 id");
+        let short_id = String::from_utf8(field().to_vec()).expect("non-utf-8 id");
+        let parents: Vec<String> = str::from_utf8(field())
+            .expect("non-utf-8 id")
+            .split(' ')
+            .filter(|parent| !parent.is_empty())
+            .map(Into::into)
+            .collect();
+        let author = String::from_utf8_lossy(field()).into_owned();
+        let timestamp = str::from_utf8(field())
+            .ok()
+            .and_then(|timestamp| timestamp.parse().ok())
+            .expect("invalid git timestamp");
+        let date = String::from_utf8_lossy(field()).into_owned();
+        let subject = String::from_utf8_lossy(field()).into_owned();
+        let mut branches = tips.remove(&id).unwrap_or_default();
+        branches.extend(inherited.remove(&id).unwrap_or_default());
+        branches.sort_unstable();
+        branches.dedup();
+        for parent in &parents {
+            inherited.entry(parent.clone()).or_default().extend(&branches);
+        }
+        let title = if title_format.is_some() {
+            let rest: Vec<_> = fields.map(String::from_utf8_lossy).collect();
+            rest.join(" ").split_whitespace().collect::<Vec<_>>().join(" ")
+        } else {
+            subject.clone()
+        };
+        visit(Commit { id, short_id, parents, author, timestamp, date, subject, title, branches });
+        buffer.clear();
+    }
+    drop(reader);
+    let status = git.wait().expect("failed to wait for git");
+    assert!(status.success(), "git returned unsuccessful status {status}");
+}
+
+/// If `tree.pretty` is a format string (rather than the name of one of git's
+/// built-in formats, which only apply to `git log`), returns it for formatting
+/// commits' titles. Native formats show one line per commit, so its line breaks
+/// are turned into spaces.
+fn title_format(git: &GitRunner) -> Option<String> {
+    let pretty = git.settings().get("tree.pretty");
+    let Some(format) = pretty.and_then(|pretty| {
+        ["format:", "tformat:"]
+            .into_iter()
+            .find_map(|prefix| pretty.strip_prefix(prefix))
+            .or_else(|| pretty.contains('%').then_some(pretty))
+    }) else {
+        return None;
+    };
+    // `%%` is a literal `%`, so it does not start a placeholder.
+    let pieces: Vec<_> = format
+        .split("%%")
+        .map(|piece| {
+            ["%n", "%x0a", "%x0A", "\n"]
+                .into_iter()
+                .fold(piece.to_owned(), |piece, line_break| piece.replace(line_break, " "))
+        })
+        .collect();
+    Some(pieces.join("%%"))
+}
+
+/// Returns the commits that --path and the `Merges` filter keep regardless of
+/// the paths they touch or whether they are merges: the branch tips, HEAD, and
+/// the merge bases.
+fn anchors(
+    git: &GitRunner,
+    selection: &Selection,
+    tips: &HashMap<String, Vec<usize>>,
+) -> HashSet<String> {
+    if selection.paths.is_empty() && selection.merges == Merges::Shown {
+        return HashSet::new();
+    }
+    tips.keys()
+        .cloned()
+        .chain(selection.merge_bases.iter().cloned())
+        .chain(git.query("rev-parse", &["--verify", "--quiet", "HEAD"]))
+        .collect()
+}
+
+/// Implements --path: limits `commits` (loaded from `revisions`) to the commits
+/// that touch `paths` plus the `anchors`, so that the graph keeps its shape.
+/// Each remaining commit's parents are replaced by its nearest remaining
+/// ancestors.
+fn apply_paths<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
+    git: &GitRunner,
+    paths: &[String],
+    revisions: I,
+    anchors: &HashSet<String>,
+    commits: Vec<Commit>,
+) -> Vec<Commit> {
+    if paths.is_empty() {
+        return commits;
+    }
+    let output = git
+        .internal("log")
+        .arg("--format=%H")
+        .args(revisions)
+        .arg("--")
+        .args(paths)
+        .output()
+        .expect("failed to run git");
+    assert!(output.status.success(), "git returned unsuccessful status {}", output.status);
+    let touching = String::from_utf8(output.stdout).expect("non-utf-8 git output");
+    let touching: HashSet<_> = touching.lines().collect();
+    skip_commits(commits, |id| touching.contains(id) || anchors.contains(id))
+}
+
+/// Returns the IDs of the commits the `merges` filter keeps (along with the
+/// `anchors`), or `None` if it keeps every commit. Computed before the commits'
+/// parents are rewritten by --path or `tree.scope`.
+fn kept_merges(
+    merges: Merges,
+    anchors: &HashSet<String>,
+    commits: &[Commit],
+) -> Option<HashSet<String>> {
+    (merges != Merges::Shown).then(|| {
+        commits
+            .iter()
+            .filter(|commit| {
+                anchors.contains(&commit.id)
+                    || (commit.parents.len() > 1) == (merges == Merges::Only)
+            })
+            .map(|commit| commit.id.clone())
+            .collect()
+    })
+}
+
+/// Implements --no-merges and --merges-only: limits `commits` to the `kept`
+/// ones, if given (see `kept_merges`).
+fn apply_merges(kept: Option<HashSet<String>>, commits: Vec<Commit>) -> Vec<Commit> {
+    match kept {
+        None => commits,
+        Some(kept) => skip_commits(commits, |id| kept.contains(id)),
+    }
+}
+
+/// Removes the commits that `keep` rejects, replacing each remaining commit's
+/// parents by its nearest remaining ancestors, so that the graph keeps its
+/// shape.
+fn skip_commits<F: Fn(&str) -> bool>(commits: Vec<Commit>, keep: F) -> Vec<Commit> {
+    let rows: HashMap<&str, usize> =
+        commits.iter().enumerate().map(|(row, commit)| (commit.id.as_str(), row)).collect();
+    // For each commit, the commits it is replaced by: itself if it is kept,
+    // otherwise its nearest kept ancestors. Parents come after their children,
+    // so this is computed from the bottom up.
+    let mut replacements: Vec<Vec<String>> = vec![vec![]; commits.len()];
+    let mut parents: Vec<Vec<String>> = vec![vec![]; commits.len()];
+    for (row, commit) in commits.iter().enumerate().rev() {
+        let mut rewritten: Vec<String> = vec![];
+        for parent in &commit.parents {
+            match rows.get(parent.as_str()) {
+                Some(&parent_row) if !keep(parent) => {
+                    for ancestor in replacements.get(parent_row).unwrap() {
+                        if !rewritten.contains(ancestor) {
+                            rewritten.push(ancestor.clone());
+                        }
+                    }
+                }
+                _ if rewritten.contains(parent) => {}
+                _ => rewritten.push(parent.clone()),
+            }
+        }
+        *replacements.get_mut(row).unwrap() =
+            if keep(&commit.id) { vec![commit.id.clone()] } else { rewritten.clone() };
+        *parents.get_mut(row).unwrap() = rewritten;
+    }
+    commits
+        .into_iter()
+        .zip(parents)
+        .filter(|pair| keep(&pair.0.id))
+        .map(|(commit, parents)| Commit { parents, ..commit })
+        .collect()
+}
+
+/// Limits `commits` (loaded from `revisions`) to the commits that touch
+/// `scope`, replacing their parents with their nearest ancestors that do. Branch
+/// membership is computed before this filtering, as a branch's tip may not touch
+/// `scope`.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+fn apply_scope<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    scope: &[String],
+    revisions: I,
+    commits: Vec<Commit>,
+) -> Vec<Commit> {
+    if scope.is_empty() {
+        return commits;
+    }
+    let mut git = git
+        .internal("rev-list")
+        .arg("--parents")
+        .args(revisions)
+        .arg("--")
+        .args(scope)
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run git");
+    let mut rewritten: HashMap<String, Vec<String>> = HashMap::new();
+    let mut reader = BufReader::new(git.stdout.as_mut().unwrap());
+    while let Some(len) = read_line(&mut reader, buffer) {
+        let mut ids = str::from_utf8(buffer.get(..len).unwrap()).expect("non-utf-8 id").split(' ');
+        let id = ids.next().expect("empty rev-list output line");
+        rewritten.insert(id.into(), ids.map(Into::into).collect());
+        buffer.clear();
+    }
+    drop(reader);
+    let status = git.wait().expect("failed to wait for git");
+    assert!(status.success(), "git returned unsuccessful status {status}");
+    commits
+        .into_iter()
+        .filter_map(|commit| {
+            rewritten.remove(&commit.id).map(|parents| Commit { parents, ..commit })
+        })
+        .collect()
+}
+
+/// Returns a map from commit ID to the indexes of the branches (or other refs)
+/// that point at that commit.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn branch_tips(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    branches: &[String],
+) -> HashMap<String, Vec<usize>> {
+    let mut tips: HashMap<String, Vec<usize>> = HashMap::new();
+    if branches.is_empty() {
+        return tips;
+    }
+    let mut git = git
+        .internal("rev-parse")
+        // Peeled so that tags (e.g. from --include-ref) map to the commits
+        // they point to.
+        .args(branches.iter().map(|branch| format!("{branch}^{{commit}}")))
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run git");
+    let mut reader = BufReader::new(git.stdout.as_mut().unwrap());
+    // rev-parse prints one ID per argument, in order.
+    for idx in 0..branches.len() {
+        let len = read_line(&mut reader, buffer).expect("missing rev-parse output");
+        let id = str::from_utf8(buffer.get(..len).unwrap()).expect("non-utf-8 id");
+        tips.entry(id.into()).or_default().push(idx);
+        buffer.clear();
+    }
+    drop(reader);
+    let status = git.wait().expect("failed to wait for git");
+    assert!(status.success(), "git returned unsuccessful status {status}");
+    tips
+}
+
+/// Returns the commits reachable from `tip` but not from the merge bases
+/// (children before parents), and the excluded commits they sit on. If `tip`
+/// is itself excluded, it is its own base.
+pub fn unique_commits(
+    git: &GitRunner,
+    tip: &str,
+    merge_bases: &[String],
+) -> (Vec<String>, Vec<String>) {
+    let output = git
+        .internal("rev-list")
+        .args(["--boundary", tip, "--not"])
+        .args(merge_bases)
+        .arg("--")
+        .output()
+        .expect("failed to run git");
+    assert!(output.status.success(), "git returned unsuccessful status {}", output.status);
+    let output = String::from_utf8(output.stdout).expect("non-utf-8 git output");
+    let (mut commits, mut bases) = (vec![], vec![]);
+    for line in output.lines() {
+        match line.strip_prefix('-') {
+            Some(base) => bases.push(base.to_owned()),
+            None => commits.push(line.to_owned()),
+        }
+    }
+    if commits.is_empty() {
+        bases.push(tip.to_owned());
+    }
+    (commits, bases)
+}
+
+/// Returns a map from merge base ID to the interesting branches that fork from
+/// it: those whose own commits sit on it, or whose tips are at it.
+pub fn base_of(git: &GitRunner, selection: &Selection) -> HashMap<String, Vec<String>> {
+    let tips = branch_tips(git, &mut vec![], &selection.interesting_branches);
+    let mut base_of: HashMap<String, Vec<String>> = HashMap::new();
+    for (idx, name) in selection.interesting_branches.iter().enumerate() {
+        let Some(id) = tips.iter().find(|tip| tip.1.contains(&idx)).map(|tip| tip.0) else {
+            continue;
+        };
+        let bases = unique_commits(git, id, &selection.merge_bases).1;
+        for base in bases.into_iter().filter(|base| selection.merge_bases.contains(base)) {
+            let names = base_of.entry(base).or_default();
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    base_of
+}
@@ -0,0 +1,50 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use std::io::{stdout, Write as _};
+
+/// Implements `git-tree compare <base>`, which prints how many commits each
+/// interesting branch is ahead of and behind `base`.
+pub fn compare(git: &GitRunner, selection: &Selection, base: &str) {
+    assert!(
+        git.query("rev-parse", &["--verify", "--quiet", &format!("{base}^{{commit}}")]).is_some(),
+        "{base} is not a commit"
+    );
+    let rows: Vec<_> = selection
+        .interesting_branches
+        .iter()
+        .map(|branch| {
+            // Prints "<behind>\t<ahead>": the left side counts commits only
+            // reachable from base.
+            let counts = git
+                .query(
+                    "rev-list",
+                    &["--left-right", "--count", &format!("{base}...{branch}"), "--"],
+                )
+                .expect("git rev-list --left-right --count failed");
+            let (behind, ahead) = counts.split_once('\t').expect("invalid rev-list output");
+            (branch, ahead.to_owned(), behind.to_owned())
+        })
+        .collect();
+    let width = rows.iter().map(|row| row.0.chars().count()).chain([6]).max().unwrap();
+    let mut out = stdout().lock();
+    writeln!(out, "{:width$}  {:>6}  {:>6}  (relative to {base})", "branch", "ahead", "behind")
+        .written();
+    for (branch, ahead, behind) in rows {
+        writeln!(out, "{branch:width$}  {ahead:>6}  {behind:>6}").written();
+    }
+}
@@ -0,0 +1,254 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `git-tree.toml`: settings files, for persistent defaults that do not belong
+//! in git's configuration. A file holds `tree.*` settings without their
+//! `tree.` prefix, e.g. `excludeBranch = ["wip/*"]` or `remotes = false`, with
+//! `[alias]` and `[label]` tables for `tree.alias.<name>` and
+//! `tree.label.<name>`. An array gives a multi-valued setting each of its
+//! values. Only this subset of TOML is understood: strings, booleans,
+//! integers, arrays of them, tables, and comments.
+
+use crate::settings::{canonical, Entry};
+use std::env::var_os;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+/// Returns the settings in `$XDG_CONFIG_HOME/git-tree/git-tree.toml` (or
+/// `~/.config/git-tree/git-tree.toml`), which apply to every repository, as
+/// `Settings` entries.
+pub fn user() -> Vec<Entry> {
+    let config_home = var_os("XDG_CONFIG_HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+    config_home.map_or_else(Vec::new, |home| read(&home.join("git-tree").join("git-tree.toml")))
+}
+
+/// Returns the settings in `git-tree.toml` in `git_dir` (the repository's
+/// `.git` directory), as `Settings` entries.
+pub fn repository(git_dir: Option<&str>) -> Vec<Entry> {
+    git_dir.map_or_else(Vec::new, |git_dir| read(&Path::new(git_dir).join("git-tree.toml")))
+}
+
+/// Reads the settings file at `path`, which is skipped if it does not exist.
+fn read(path: &Path) -> Vec<Entry> {
+    read_to_string(path).map_or_else(|_| vec![], |text| parse(path, &text))
+}
+
+/// Parses the contents of the settings file at `path`.
+fn parse(path: &Path, text: &str) -> Vec<Entry> {
+    let mut entries = vec![];
+    let mut table = String::new();
+    let mut rest = skip_blank(text);
+    while !rest.is_empty() {
+        let parsed = if let Some(header) = rest.strip_prefix('[') {
+            header.split_once(']').filter(|header| !header.0.contains('\n')).map(|(name, after)| {
+                name.trim().clone_into(&mut table);
+                after
+            })
+        } else {
+            entry(rest).map(|(key, values, after)| {
+                let key = if table.is_empty() {
+                    canonical(&format!("tree.{key}"))
+                } else {
+                    canonical(&format!("tree.{table}.{key}"))
+                };
+                entries.extend(values.into_iter().map(|value| (key.clone(), Some(value))));
+                after
+            })
+        };
+        // Each header and entry must end its line.
+        let after = parsed
+            .map(|after| after.trim_start_matches([' ', '\t']))
+            .filter(|after| after.is_empty() || after.starts_with(['\r', '\n', '#']));
+        let offset = text.len().saturating_sub(rest.len());
+        let line = text.get(..offset).unwrap().lines().count().saturating_add(1);
+        assert!(after.is_some(), "{}:{line}: invalid git-tree.toml line", path.display());
+        rest = skip_blank(after.unwrap());
+    }
+    entries
+}
+
+/// Skips whitespace (including line breaks) and comments.
+fn skip_blank(mut text: &str) -> &str {
+    loop {
+        text = text.trim_start();
+        let Some(comment) = text.strip_prefix('#') else { return text };
+        text = comment.split_once('\n').map_or("", |(_, next)| next);
+    }
+}
+
+/// Parses a `key = value` entry at the start of `text`, returning the key, the
+/// values (more than one for an array), and the text after the value.
+fn entry(text: &str) -> Option<(&str, Vec<String>, &str)> {
+    let Some((key, value)) = text.split_once('=') else { return None };
+    let key = key.trim();
+    let key = key.strip_prefix('"').and_then(|key| key.strip_suffix('"')).unwrap_or(key);
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return None;
+    }
+    let value_text = value.trim_start_matches([' ', '\t']);
+    let Some(mut rest) = value_text.strip_prefix('[') else {
+        return scalar(value_text).map(|(scalar, after)| (key, vec![scalar], after));
+    };
+    let mut values = vec![];
+    loop {
+        rest = skip_blank(rest);
+        if let Some(after) = rest.strip_prefix(']') {
+            return Some((key, values, after));
+        }
+        let Some((item, after_item)) = scalar(rest) else { return None };
+        values.push(item);
+        rest = skip_blank(after_item);
+        if let Some(after_comma) = rest.strip_prefix(',') {
+            rest = after_comma;
+        } else if !rest.starts_with(']') {
+            return None;
+        }
+    }
+}
+
+/// Parses a string, boolean, or integer at the start of `text`, returning it
+/// (as `git config` would print it) and the text after it.
+fn scalar(text: &str) -> Option<(String, &str)> {
+    if let Some(body) = text.strip_prefix('"') {
+        return basic_string(body);
+    }
+    if let Some(body) = text.strip_prefix('\'') {
+        return body
+            .split_once('\'')
+            .filter(|literal| !literal.0.contains('\n'))
+            .map(|(literal, after)| (literal.into(), after));
+    }
+    let end = text.find([',', ']', '#', ' ', '\t', '\r', '\n']).unwrap_or(text.len());
+    let (token, after) = text.split_at(end);
+    let valid = matches!(token, "true" | "false") || token.replace('_', "").parse::<i64>().is_ok();
+    valid.then(|| (token.replace('_', ""), after))
+}
+
+/// Parses the rest of a double-quoted string, whose opening quote has been
+/// removed from `body`, handling the common escapes.
+fn basic_string(body: &str) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = body.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => {
+                return body
+                    .get(idx..)
+                    .and_then(|end| end.strip_prefix('"'))
+                    .map(|after| (value, after))
+            }
+            '\\' => {
+                let escaped = match chars.next().map(|(_, escaped)| escaped) {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    _ => return None,
+                };
+                value.push(escaped);
+            }
+            '\n' => return None,
+            _ => value.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use std::path::Path;
+
+    /// Parses `text`, returning the entries with their values unwrapped.
+    fn entries(text: &str) -> Vec<(String, String)> {
+        parse(Path::new("git-tree.toml"), text)
+            .into_iter()
+            .map(|(key, value)| (key, value.unwrap()))
+            .collect()
+    }
+
+    /// Returns `(key, value)` as owned strings.
+    fn entry(key: &str, value: &str) -> (String, String) {
+        (key.into(), value.into())
+    }
+
+    #[test]
+    fn parses_scalars() {
+        let text = "remotes = false\nmaxBranches = 1_000\ndefaultArgs = '--oneline'\n";
+        assert_eq!(
+            entries(text),
+            [
+                entry("tree.remotes", "false"),
+                entry("tree.maxbranches", "1000"),
+                entry("tree.defaultargs", "--oneline"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_escapes() {
+        let text = r#"pretty = "%h\t\"%s\"\\n""#;
+        assert_eq!(entries(text), [entry("tree.pretty", "%h\t\"%s\"\\n")]);
+    }
+
+    #[test]
+    fn parses_arrays() {
+        let text = "excludeBranch = [\n  \"wip/*\", # drafts\n  'dependabot/*',\n]\n";
+        assert_eq!(
+            entries(text),
+            [entry("tree.excludebranch", "wip/*"), entry("tree.excludebranch", "dependabot/*")]
+        );
+    }
+
+    #[test]
+    fn parses_tables() {
+        let text = "stats = \"all\"\n[alias]\nReview = \"--mine\"\n[label]\n\"v1.0\" = 'first'\n";
+        assert_eq!(
+            entries(text),
+            [
+                entry("tree.stats", "all"),
+                entry("tree.alias.review", "--mine"),
+                entry("tree.label.v1.0", "first"),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_comments_and_blank_lines() {
+        let text = "# settings\n\n  remotes = true # inline\r\n\n# end";
+        assert_eq!(entries(text), [entry("tree.remotes", "true")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "git-tree.toml:2: invalid git-tree.toml line")]
+    fn rejects_bare_words() {
+        entries("remotes = true\nstats = all\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "git-tree.toml:1: invalid git-tree.toml line")]
+    fn rejects_unterminated_strings() {
+        entries("pretty = \"%h\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "git-tree.toml:1: invalid git-tree.toml line")]
+    fn rejects_trailing_text() {
+        entries("remotes = true false\n");
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support for Conventional Commits (<https://www.conventionalcommits.org/>)
+//! subject lines, e.g. `feat(parser)!: add an option`.
+
+use crate::git_runner::GitRunner;
+
+/// The recognized commit types, in display order, with their section headings.
+pub const TYPES: [(&str, &str); 11] = [
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("refactor", "Refactoring"),
+    ("docs", "Documentation"),
+    ("test", "Tests"),
+    ("build", "Build"),
+    ("ci", "Continuous Integration"),
+    ("style", "Style"),
+    ("chore", "Chores"),
+    ("revert", "Reverts"),
+];
+
+/// Heading for commits that do not have a recognized type.
+pub const OTHER: &str = "Other";
+
+/// Returns whether grouping by commit type is enabled (`tree.conventional`).
+pub fn enabled(git: &GitRunner) -> bool {
+    git.settings().get_bool("tree.conventional") == Some(true)
+}
+
+/// Returns the index into `TYPES` of `subject`'s type, or `None` if `subject`
+/// does not start with a recognized type.
+pub fn commit_type(subject: &str) -> Option<usize> {
+    let Some((prefix, _)) = subject.split_once(':') else { return None };
+    let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+    // Strip the optional scope.
+    let name = match prefix.split_once('(') {
+        Some((name, scope)) if scope.ends_with(')') => name,
+        Some(_) => return None,
+        None => prefix,
+    };
+    TYPES.iter().position(|&(known, _)| known.eq_ignore_ascii_case(name))
+}
@@ -0,0 +1,162 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `tree.decorateHide`: refs whose labels are hidden. Hidden refs still take
+//! part in commit selection.
+
+use crate::git_runner::GitRunner;
+use std::collections::HashSet;
+
+/// Returns the `tree.decorateHide` patterns.
+pub fn hide_patterns(git: &GitRunner) -> Vec<String> {
+    git.settings().get_all("tree.decorateHide").into_iter().map(Into::into).collect()
+}
+
+/// Returns the short names (as used in labels) of the refs matching `patterns`.
+pub fn hidden_refs(git: &GitRunner, patterns: &[String]) -> HashSet<String> {
+    if patterns.is_empty() {
+        return HashSet::new();
+    }
+    let patterns: Vec<_> = patterns.iter().flat_map(|pattern| normalize(pattern)).collect();
+    let refs =
+        git.query("for-each-ref", &["--format=%(refname) %(refname:short)"]).unwrap_or_default();
+    refs.lines()
+        .filter_map(|line| line.split_once(' '))
+        .filter(|&(refname, _)| {
+            patterns.iter().any(|pattern| glob_matches(pattern.as_bytes(), refname.as_bytes()))
+        })
+        .map(|(_, short)| short.into())
+        .collect()
+}
+
+/// Interprets a pattern the way `git log --decorate-refs-exclude` does, so that
+/// native output and `git log` hide the same refs: `refs/` is prepended if
+/// missing, and a pattern without wildcards matches that ref and the refs below
+/// it. Returns equivalent glob patterns.
+fn normalize(pattern: &str) -> Vec<String> {
+    let normalized =
+        if pattern.starts_with("refs/") { pattern.into() } else { format!("refs/{pattern}") };
+    if pattern.contains(['*', '?', '[']) {
+        return vec![normalized];
+    }
+    let below = format!("{}/*", normalized.trim_end_matches('/'));
+    vec![normalized, below]
+}
+
+/// Returns whether `text` matches the glob `pattern`, in which `*` matches any
+/// sequence of characters (including `/`), `?` matches any one character, and
+/// a bracket class such as `[0-9]` (or `[!0-9]`) matches any one character in
+/// (or not in) it. A `[` without a closing `]` matches itself.
+pub fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((&b'*', rest)) => {
+            (0..=text.len()).any(|skip| glob_matches(rest, text.get(skip..).unwrap()))
+        }
+        Some((&b'[', rest)) if class_end(rest).is_some() => {
+            let (class, after) = rest.split_at(class_end(rest).unwrap());
+            let (negated, members) = match class.split_first() {
+                Some((&(b'!' | b'^'), members)) => (true, members),
+                _ => (false, class),
+            };
+            text.split_first().is_some_and(|(&t, text)| {
+                class_contains(members, t) != negated && glob_matches(after.get(1..).unwrap(), text)
+            })
+        }
+        Some((&c, rest)) => text
+            .split_first()
+            .is_some_and(|(&t, text)| (c == b'?' || c == t) && glob_matches(rest, text)),
+    }
+}
+
+/// Returns the index of the `]` that closes the bracket class `class` (the
+/// pattern after its `[`) starts, if there is one. A `]` at the start of the
+/// class (after any `!` or `^`) is a member rather than the end.
+fn class_end(class: &[u8]) -> Option<usize> {
+    let first = usize::from(matches!(class.first(), Some(&(b'!' | b'^')))).saturating_add(1);
+    class
+        .get(first..)
+        .and_then(|rest| rest.iter().position(|&b| b == b']'))
+        .map(|idx| idx.saturating_add(first))
+}
+
+/// Returns whether `byte` is one of a bracket class's `members`, which are
+/// characters and ranges such as `a-z`.
+fn class_contains(members: &[u8], byte: u8) -> bool {
+    let Some((&low, rest)) = members.split_first() else { return false };
+    if let (Some(&b'-'), Some(&high)) = (rest.first(), rest.get(1)) {
+        return (low..=high).contains(&byte) || class_contains(rest.get(2..).unwrap(), byte);
+    }
+    low == byte || class_contains(rest, byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{class_end, glob_matches};
+
+    /// Returns whether `pattern` matches `text`.
+    fn matches(pattern: &str, text: &str) -> bool {
+        glob_matches(pattern.as_bytes(), text.as_bytes())
+    }
+
+    #[test]
+    fn star_matches_any_run() {
+        assert!(matches("wip/*", "wip/"));
+        assert!(matches("wip/*", "wip/a/b"));
+        assert!(matches("*-old", "topic-old"));
+        assert!(!matches("wip/*", "main"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_character() {
+        assert!(matches("v?", "v1"));
+        assert!(!matches("v?", "v"));
+        assert!(!matches("v?", "v10"));
+    }
+
+    #[test]
+    fn class_matches_members() {
+        assert!(matches("[abc]x", "bx"));
+        assert!(!matches("[abc]x", "dx"));
+        assert!(!matches("[abc]", ""));
+    }
+
+    #[test]
+    fn negated_class_matches_non_members() {
+        assert!(matches("[!a]", "b"));
+        assert!(matches("[^a]", "b"));
+        assert!(!matches("[!a]", "a"));
+    }
+
+    #[test]
+    fn class_matches_ranges() {
+        assert!(matches("v[0-9]", "v7"));
+        assert!(matches("[a-cx]", "x"));
+        assert!(!matches("v[0-9]", "vx"));
+    }
+
+    #[test]
+    fn unclosed_bracket_is_literal() {
+        assert_eq!(class_end(b"ab"), None);
+        assert!(matches("a[b", "a[b"));
+        assert!(!matches("a[b", "ab"));
+    }
+
+    #[test]
+    fn leading_bracket_is_a_member() {
+        assert_eq!(class_end(b"]a]"), Some(2));
+        assert_eq!(class_end(b"!]]"), Some(2));
+        assert!(matches("[]a]", "]"));
+    }
+}
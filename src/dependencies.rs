@@ -0,0 +1,59 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::commits::branch_tips;
+use crate::git_runner::GitRunner;
+use crate::selection::Selection;
+use std::collections::HashMap;
+
+/// Returns a map from interesting branch to the interesting branches it
+/// depends on, per the `Depends-on:` and `Based-on:` trailers of its tip
+/// commit, for teams that record the order of a stack of branches in commit
+/// messages. Trailers naming branches that are not interesting are ignored.
+pub fn dependencies(git: &GitRunner, selection: &Selection) -> HashMap<String, Vec<String>> {
+    let tips = branch_tips(git, &mut vec![], &selection.interesting_branches);
+    if tips.is_empty() {
+        return HashMap::new();
+    }
+    let output = git
+        .internal("log")
+        .args([
+            "--no-walk=unsorted",
+            "--format=%H%x00%(trailers:key=Depends-on,key=Based-on,valueonly,unfold,separator=%x00)",
+        ])
+        .args(tips.keys())
+        .arg("--")
+        .output()
+        .expect("failed to run git");
+    assert!(output.status.success(), "git returned unsuccessful status {}", output.status);
+    let output = String::from_utf8_lossy(&output.stdout);
+    let mut dependencies: HashMap<String, Vec<String>> = HashMap::new();
+    for line in output.lines() {
+        let mut fields = line.split('\0');
+        let id = fields.next().expect("empty git log output line");
+        let names: Vec<_> = fields
+            .map(str::trim)
+            .filter(|&name| selection.interesting_branches.iter().any(|branch| branch == name))
+            .collect();
+        for &idx in tips.get(id).into_iter().flatten() {
+            let branch = selection.interesting_branches.get(idx).unwrap();
+            for &name in names.iter().filter(|&name| name != branch) {
+                let entry = dependencies.entry(branch.clone()).or_default();
+                if !entry.iter().any(|dependency| dependency == name) {
+                    entry.push(name.into());
+                }
+            }
+        }
+    }
+    dependencies
+}
@@ -0,0 +1,94 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commits::{outstanding_commits, Commit};
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use core::iter::repeat_n;
+use std::io::{stdout, Write as _};
+
+/// Width of each side of the output, in characters.
+const SIDE: usize = 40;
+
+/// Implements `git-tree diff-branches <a> <b>`, which shows the commits only on
+/// `a` and only on `b` side by side, followed by their common base.
+/// Precondition: `buffer` must be empty.
+pub fn diff_branches(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    selection: &Selection,
+    branches: &(String, String),
+) {
+    let index = |name: &str| {
+        let idx = selection.interesting_branches.iter().position(|branch| branch == name);
+        assert!(idx.is_some(), "{name} is not an interesting branch");
+        idx.unwrap()
+    };
+    let (a, b) = (index(&branches.0), index(&branches.1));
+    let commits = outstanding_commits(git, buffer, selection);
+    let on = |commit: &&Commit, idx| commit.branches.binary_search(&idx).is_ok();
+    let only = |idx, other| -> Vec<_> {
+        commits.iter().filter(|commit| on(commit, idx) && !on(commit, other)).collect()
+    };
+    let (only_a, only_b) = (only(a, b), only(b, a));
+    // The outstanding commits are in topological order, so the first one on
+    // both branches is their newest common commit. If there is none, the
+    // branches meet at or below the merge bases.
+    let base = commits.iter().find(|commit| on(commit, a) && on(commit, b)).map_or_else(
+        || {
+            git.query("merge-base", &[&branches.0, &branches.1])
+                .and_then(|id| git.query("log", &["-1", "--format=%h %s", &id, "--"]))
+                .unwrap_or_else(|| "(none)".into())
+        },
+        |commit| format!("{} {}", commit.short_id, commit.title),
+    );
+
+    let mut out = stdout().lock();
+    let header = |name: &str, count: usize| format!("only on {name} ({count})");
+    writeln!(
+        out,
+        "{:SIDE$} | {}",
+        truncate(&header(&branches.0, only_a.len())),
+        truncate(&header(&branches.1, only_b.len()))
+    )
+    .written();
+    writeln!(
+        out,
+        "{}",
+        repeat_n('-', SIDE.saturating_mul(2).saturating_add(3)).collect::<String>()
+    )
+    .written();
+    let line = |commit: Option<&&Commit>| {
+        commit.map_or_else(String::new, |commit| {
+            truncate(&format!("{} {}", commit.short_id, commit.title))
+        })
+    };
+    for row in 0..only_a.len().max(only_b.len()) {
+        let right = line(only_b.get(row));
+        let left = line(only_a.get(row));
+        writeln!(out, "{left:SIDE$} | {right}").written();
+    }
+    writeln!(out, "common base: {base}").written();
+}
+
+/// Shortens `text` to fit in one side of the output.
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= SIDE {
+        return text.into();
+    }
+    let mut truncated: String = text.chars().take(SIDE.saturating_sub(2)).collect();
+    truncated.push_str("..");
+    truncated
+}
@@ -0,0 +1,56 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use std::io::{stdout, IsTerminal as _, Write as _};
+
+/// Prints a line summarizing uncommitted changes (staged, modified, and
+/// untracked files) above the `git log` output. Only shown on a terminal, and
+/// can be disabled by setting `tree.statusHeader` to false. Nothing is printed
+/// if there is no working tree.
+pub fn write_header(git: &GitRunner) {
+    if !stdout().is_terminal() || git.settings().get_bool("tree.statusHeader") == Some(false) {
+        return;
+    }
+    if let Some(summary) = summary(git) {
+        writeln!(stdout().lock(), "working tree: {summary}").written();
+    }
+}
+
+/// Summarizes the uncommitted changes, e.g. `1 staged, 2 modified, 0
+/// untracked` or `clean`. Returns `None` if there is no working tree.
+pub fn summary(git: &GitRunner) -> Option<String> {
+    let Some(status) = git.query("status", &["--porcelain"]) else { return None };
+    let (mut staged, mut modified, mut untracked): (usize, usize, usize) = (0, 0, 0);
+    for line in status.lines() {
+        let mut codes = line.chars();
+        match (codes.next(), codes.next()) {
+            (Some('?'), Some('?')) => untracked = untracked.saturating_add(1),
+            (index, worktree) => {
+                if index.is_some_and(|code| code != ' ') {
+                    staged = staged.saturating_add(1);
+                }
+                if worktree.is_some_and(|code| code != ' ') {
+                    modified = modified.saturating_add(1);
+                }
+            }
+        }
+    }
+    Some(if staged == 0 && modified == 0 && untracked == 0 {
+        "clean".into()
+    } else {
+        format!("{staged} staged, {modified} modified, {untracked} untracked")
+    })
+}
@@ -0,0 +1,103 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commits::{branch_tips, displayed_commits};
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use std::collections::HashMap;
+use std::io::{stdout, Write as _};
+
+/// The start of the script: it creates the repository, and defines `commit`,
+/// which creates the empty commit numbered `$1` with the parents given as the
+/// remaining arguments. Fixed identities and dates keep the commit IDs the same
+/// on every run.
+const PROLOGUE: &str = r#"#!/bin/sh
+# Generated by git-tree export --script. Run it in an empty directory to
+# recreate the branch topology that git-tree showed, with empty commits.
+set -e
+git init -q
+export GIT_AUTHOR_NAME=git-tree GIT_AUTHOR_EMAIL=git-tree@example.com
+export GIT_COMMITTER_NAME=git-tree GIT_COMMITTER_EMAIL=git-tree@example.com
+tree=$(git mktree </dev/null)
+commit() {
+	n=$1
+	shift
+	GIT_AUTHOR_DATE="@$((1700000000 + n)) +0000" GIT_COMMITTER_DATE="@$((1700000000 + n)) +0000" \
+		git commit-tree "$tree" -m "c$n" "$@"
+}
+# c0 stands for the history below the merge bases.
+c0=$(commit 0)"#;
+
+/// Implements `git-tree export --script`, which prints a shell script that
+/// reproduces the displayed commits' graph with empty commits, along with the
+/// interesting branches (under their full ref names) and HEAD. The script is
+/// meant for bug reports and test fixtures, so commit messages, authors, and
+/// contents are not copied.
+/// Precondition: `buffer` must be empty.
+pub fn export(git: &GitRunner, buffer: &mut Vec<u8>, selection: &Selection) {
+    let commits = displayed_commits(git, buffer, selection);
+    let mut out = stdout().lock();
+    writeln!(out, "{PROLOGUE}").written();
+    // Parents are created before their children, and numbered from 1.
+    let mut numbers = HashMap::new();
+    let first: usize = 1;
+    for (commit, number) in commits.iter().rev().zip(first..) {
+        let mut parents: Vec<_> = commit
+            .parents
+            .iter()
+            .map(|parent| numbers.get(parent.as_str()).copied().unwrap_or_default())
+            .collect();
+        // Several parents outside the displayed commits all become c0.
+        parents.dedup();
+        if parents.is_empty() {
+            parents.push(0);
+        }
+        let options: Vec<_> = parents.iter().map(|parent| format!(" -p \"$c{parent}\"")).collect();
+        writeln!(out, "c{number}=$(commit {number}{})", options.concat()).written();
+        numbers.insert(commit.id.as_str(), number);
+    }
+    let tips = branch_tips(git, buffer, &selection.interesting_branches);
+    let mut refs: Vec<_> =
+        tips.iter().flat_map(|tip| tip.1.iter().map(|&idx| (idx, tip.0.as_str()))).collect();
+    // In the order of the interesting branches, so the script is the same on
+    // every run.
+    refs.sort_unstable();
+    for (idx, id) in refs {
+        let name = selection.interesting_branches.get(idx).unwrap();
+        let number = numbers.get(id).copied().unwrap_or_default();
+        // Reflog selectors (stash@{1}) and worktree HEADs have no ref of their
+        // own.
+        let full = git.query("rev-parse", &["--symbolic-full-name", name, "--"]);
+        match full.as_deref().and_then(|full| full.lines().next()) {
+            Some(full) if full.starts_with("refs/") && full != "refs/stash" => {
+                writeln!(out, "git update-ref {} \"$c{number}\"", quote(full))
+            }
+            _ => writeln!(out, "# {name} is not a ref; it points at c{number}"),
+        }
+        .written();
+    }
+    let head = git.query("rev-parse", &["--verify", "--quiet", "HEAD"]).unwrap_or_default();
+    let number = numbers.get(head.as_str()).copied().unwrap_or_default();
+    match git.query("symbolic-ref", &["--quiet", "HEAD"]) {
+        Some(branch) => writeln!(out, "git symbolic-ref HEAD {}", quote(&branch)),
+        None => writeln!(out, "git update-ref --no-deref HEAD \"$c{number}\""),
+    }
+    .written();
+}
+
+/// Quotes `text` for the shell.
+pub fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
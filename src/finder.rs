@@ -0,0 +1,55 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lets the user pick an interesting branch with the `fzf` fuzzy finder.
+
+use crate::selection::Selection;
+use std::io::Write as _;
+use std::process::{Command, Output, Stdio};
+
+/// Runs `fzf` over the interesting branches, previewing each branch's commits
+/// since the merge bases. Returns the chosen branch, or `None` if the user
+/// cancelled.
+pub fn choose(selection: &Selection) -> Option<String> {
+    let branches = selection.sorted_branches();
+    // fzf substitutes the quoted branch name for {}. Merge bases are commit
+    // IDs, which need no quoting.
+    let preview = format!(
+        "git log --oneline --color=always {{}} --not {} --",
+        selection.merge_bases.join(" ")
+    );
+    let output = run_fzf(&branches, &preview);
+    // fzf exits with 1 if nothing matched and 130 if the user cancelled.
+    if !output.status.success() {
+        return None;
+    }
+    let chosen = String::from_utf8(output.stdout).expect("non-utf-8 fzf output");
+    Some(chosen.trim_end().to_owned()).filter(|chosen| !chosen.is_empty())
+}
+
+/// Runs `fzf` with `preview` as its preview command, offering `items`.
+fn run_fzf(items: &[&str], preview: &str) -> Output {
+    let mut fzf = Command::new("fzf")
+        .args(["--no-multi", "--prompt=branch> ", "--preview", preview])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run fzf (is it installed?)");
+    let mut input = fzf.stdin.take().unwrap();
+    for item in items {
+        writeln!(input, "{item}").expect("fzf write failed");
+    }
+    drop(input);
+    fzf.wait_with_output().expect("failed to wait for fzf")
+}
@@ -0,0 +1,81 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Links to commits on the repository's forge (GitHub, GitLab, etc.).
+
+use crate::git_runner::GitRunner;
+
+/// Generates web links to commits.
+pub struct Forge {
+    /// The repository's web URL, e.g. `https://github.com/google/git-tree`.
+    web_url: String,
+}
+
+impl Forge {
+    /// Returns the forge hosting `origin` (or the only remote, if there is no
+    /// `origin`), or `None` if its URL is not recognized.
+    pub fn detect(git: &GitRunner) -> Option<Self> {
+        let remotes = git.query("remote", &[]).unwrap_or_default();
+        let remote = if remotes.lines().any(|remote| remote == "origin") {
+            "origin"
+        } else {
+            let mut remotes = remotes.lines();
+            match (remotes.next(), remotes.next()) {
+                (Some(remote), None) => remote,
+                _ => return None,
+            }
+        };
+        // get-url applies url.<base>.insteadOf (and, with --push,
+        // pushInsteadOf) rewrites, which the raw remote.<name>.url lacks. The
+        // push URL is a fallback for fetch URLs that are not on a forge, such as
+        // a local mirror.
+        let url = [vec!["get-url", remote], vec!["get-url", "--push", remote]]
+            .into_iter()
+            .filter_map(|args| git.query("remote", &args))
+            .find_map(|url| web_url(&url));
+        url.map(|web_url| Self { web_url })
+    }
+
+    /// Returns the URL of the page showing commit `id`.
+    pub fn commit_url(&self, id: &str) -> String {
+        format!("{}/commit/{id}", self.web_url)
+    }
+}
+
+/// Converts a clone URL (`https://host/path.git`, `ssh://git@host/path`, or
+/// `git@host:path`) into the corresponding web URL.
+fn web_url(url: &str) -> Option<String> {
+    let url = url.strip_suffix('/').unwrap_or(url);
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    if let Some(rest) = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://")) {
+        // Drop any credentials.
+        let rest = rest.split_once('@').map_or(rest, |(_, rest)| rest);
+        return Some(format!("https://{rest}"));
+    }
+    if let Some(rest) = url.strip_prefix("ssh://") {
+        let rest = rest.split_once('@').map_or(rest, |(_, rest)| rest);
+        return rest.split_once('/').map(|(host, path)| {
+            // Drop the port, which is the SSH port rather than the web port.
+            let host = host.split_once(':').map_or(host, |(host, _)| host);
+            format!("https://{host}/{path}")
+        });
+    }
+    // scp-like syntax: [user@]host:path
+    let Some((host, path)) = url.split_once(':') else { return None };
+    let host = host.split_once('@').map_or(host, |(_, host)| host);
+    if host.contains('/') || path.starts_with('/') {
+        return None;
+    }
+    Some(format!("https://{host}/{path}"))
+}
@@ -0,0 +1,49 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Finds each branch's tip from before its most recent rebase or amend, using
+//! the branch reflogs.
+
+use crate::git_runner::GitRunner;
+use crate::reflog::{reflog, ReflogEntry};
+use crate::selection::Selection;
+
+/// A branch's tip before its most recent rebase or amend.
+pub struct Ghost {
+    pub id: String,
+
+    /// The reflog entry naming the old tip, e.g. `feature@{2}`.
+    pub selector: String,
+}
+
+/// Returns the ghost of each interesting local branch that has been rebased or
+/// amended. Branches without a reflog (such as remote-tracking branches, which
+/// are only rewritten by fetching) have no ghost.
+pub fn ghosts(git: &GitRunner, selection: &Selection) -> Vec<Ghost> {
+    selection
+        .interesting_branches
+        .iter()
+        .filter_map(|branch| {
+            // Entries are newest first, so the entry after the most recent
+            // rewrite is the tip it replaced.
+            let entries = reflog(git, branch);
+            let rewrite = entries.iter().position(ReflogEntry::is_rewrite);
+            let Some(old) = rewrite.and_then(|rewrite| entries.get(rewrite.saturating_add(1)))
+            else {
+                return None;
+            };
+            Some(Ghost { id: old.id.clone(), selector: old.selector.clone() })
+        })
+        .collect()
+}
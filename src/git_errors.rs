@@ -0,0 +1,56 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::signals;
+use std::io::{stderr, Read as _, Write as _};
+use std::process::{Command, Stdio};
+use std::thread::spawn;
+
+/// The exit status git uses for fatal errors, such as an unknown revision.
+const FATAL: i32 = 128;
+
+/// Runs the user-facing `log`, handling its errors per `tree.onGitError`: if
+/// git exits with status 128, its standard error is dropped (`silent`),
+/// summarized in one line (`message`), or passed through unchanged (`raw`, the
+/// default). Other failures always pass git's standard error through. Either
+/// way, git-tree exits with git's exit status if git fails.
+pub fn run(git: &GitRunner, mut log: Command) {
+    let mode = git.settings().get("tree.onGitError").unwrap_or("raw");
+    assert!(matches!(mode, "silent" | "message" | "raw"), "invalid tree.onGitError {mode}");
+    if mode == "raw" {
+        return signals::exit_like(signals::wait(log.spawn().expect("Failed to run git")));
+    }
+    let mut child = log.stderr(Stdio::piped()).spawn().expect("Failed to run git");
+    // Read concurrently so git cannot block on a full pipe.
+    let mut pipe = child.stderr.take().expect("missing git stderr");
+    let reader = spawn(move || {
+        let mut text = String::new();
+        pipe.read_to_string(&mut text).expect("git stderr read failed");
+        text
+    });
+    let status = signals::wait(child);
+    let text = reader.join().expect("stderr reading thread panicked");
+    let mut out = stderr().lock();
+    if status.code() != Some(FATAL) {
+        out.write_all(text.as_bytes()).expect("stderr write failed");
+    } else if mode == "message" {
+        let line =
+            text.lines().find(|line| line.starts_with("fatal: ")).or_else(|| text.lines().next());
+        let message = line.map_or("", |line| line.trim_start_matches("fatal: "));
+        writeln!(out, "git-tree: git log failed: {message}").expect("stderr write failed");
+    }
+    drop(out);
+    signals::exit_like(status);
+}
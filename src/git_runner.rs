@@ -0,0 +1,294 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config_file;
+use crate::settings::Settings;
+use crate::watched::Watched;
+use core::str;
+use core::time::Duration;
+use std::env::{split_paths, var_os};
+use std::ffi::OsString;
+use std::io::BufRead;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Configuration that changes the output of the internal commands, pinned to
+/// the values git-tree's parsing expects (`-c` takes precedence over every
+/// configuration file). Paths are printed unquoted.
+const PINNED_CONFIG: [&str; 6] = [
+    "core.quotePath=false",
+    "diff.noprefix=false",
+    "log.abbrevCommit=false",
+    "log.decorate=short",
+    "log.follow=false",
+    "log.showSignature=false",
+];
+
+/// Constructs every git invocation that git-tree makes. The internal queries
+/// and the final `git log` must agree on what the repository looks like, so
+/// settings that affect the object graph are applied here rather than by each
+/// caller.
+pub struct GitRunner {
+    /// The git executable.
+    program: PathBuf,
+
+    /// Whether git should ignore replacement refs (see `git replace`).
+    no_replace_objects: bool,
+
+    /// The repository to run git in (passed as `git -C`), if not the current
+    /// directory.
+    repo: Option<String>,
+
+    /// The repository (`git --git-dir`) and working tree (`git --work-tree`) to
+    /// use, if given.
+    git_dir: Option<String>,
+    work_tree: Option<String>,
+
+    /// The `tree.*` settings.
+    settings: Settings,
+
+    /// How long `spawn_watched` commands may run, from `tree.timeout`.
+    timeout: Option<Duration>,
+}
+
+impl GitRunner {
+    /// If `no_replace_objects` is `None`, the `GIT_NO_REPLACE_OBJECTS`
+    /// environment variable decides whether replacement refs are ignored.
+    /// `git_dir` and `work_tree` are the values of --git-dir and --work-tree.
+    pub fn new(
+        no_replace_objects: Option<bool>,
+        repo: Option<String>,
+        git_dir: Option<String>,
+        work_tree: Option<String>,
+    ) -> Self {
+        let mut runner = Self {
+            program: if cfg!(windows) { locate_git() } else { "git".into() },
+            no_replace_objects: no_replace_objects
+                .unwrap_or_else(|| var_os("GIT_NO_REPLACE_OBJECTS").is_some()),
+            repo,
+            git_dir,
+            work_tree,
+            settings: Settings::default(),
+            timeout: None,
+        };
+        let absolute_git_dir = runner.query("rev-parse", &["--absolute-git-dir"]);
+        let user_file = config_file::user();
+        let repo_file = config_file::repository(absolute_git_dir.as_deref());
+        runner.settings = Settings::load(runner.internal("config"), user_file, repo_file);
+        runner.timeout = runner
+            .settings
+            .get("tree.timeout")
+            .map(|seconds| Duration::from_secs(seconds.parse().expect("invalid tree.timeout")))
+            .filter(|timeout| !timeout.is_zero());
+        runner
+    }
+
+    /// Returns this runner if `new` would create an identical one from these
+    /// arguments, so that the settings are not read again, and otherwise a new
+    /// runner.
+    pub fn reuse(
+        self,
+        no_replace_objects: Option<bool>,
+        repo: Option<String>,
+        git_dir: Option<String>,
+        work_tree: Option<String>,
+    ) -> Self {
+        let same_objects = no_replace_objects
+            .unwrap_or_else(|| var_os("GIT_NO_REPLACE_OBJECTS").is_some())
+            == self.no_replace_objects;
+        if same_objects
+            && repo == self.repo
+            && git_dir == self.git_dir
+            && work_tree == self.work_tree
+        {
+            return self;
+        }
+        Self::new(no_replace_objects, repo, git_dir, work_tree)
+    }
+
+    /// Returns the `tree.*` settings, which are read once when the runner is
+    /// created.
+    pub const fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Returns a `git <subcommand>` invocation whose output is shown to the
+    /// user, and therefore respects the user's pager, color, and locale
+    /// settings.
+    pub fn command(&self, subcommand: &str) -> Command {
+        let mut command = Command::new(&self.program);
+        self.add_global_options(&mut command);
+        command.arg(subcommand);
+        command
+    }
+
+    /// Returns a `git <subcommand>` invocation whose output git-tree parses.
+    /// The user's pager, color, and locale settings, and the configuration in
+    /// `PINNED_CONFIG`, are overridden so they cannot change the output format.
+    pub fn internal(&self, subcommand: &str) -> Command {
+        let mut command = Command::new(&self.program);
+        command.env("GIT_PAGER", "cat").env("LC_ALL", "C").env_remove("LANGUAGE");
+        command.args(["--no-pager", "-c", "color.ui=never"]);
+        for setting in PINNED_CONFIG {
+            command.args(["-c", setting]);
+        }
+        self.add_global_options(&mut command);
+        command.arg(subcommand);
+        command
+    }
+
+    /// Runs an internal git command that prints a single value, returning that
+    /// value. Returns `None` if git exits unsuccessfully (e.g. a ref or config
+    /// value does not exist).
+    pub fn query(&self, subcommand: &str, args: &[&str]) -> Option<String> {
+        let output = self.internal(subcommand).args(args).output().expect("failed to run git");
+        if !output.status.success() {
+            return None;
+        }
+        Some(str::from_utf8(&output.stdout).expect("non-utf-8 git output").trim_end().into())
+    }
+
+    /// Spawns `command` (from `internal`) with its stdout piped, killing it if
+    /// it runs longer than `tree.timeout` seconds. Used for the commands that
+    /// are prone to hanging on a misbehaving repository.
+    pub fn spawn_watched(&self, command: &mut Command) -> Watched {
+        Watched::spawn(command, self.timeout)
+    }
+
+    /// Adds the options shared by every invocation to `command`.
+    fn add_global_options(&self, command: &mut Command) {
+        if let Some(repo) = self.repo.as_deref() {
+            command.args(["-C", repo]);
+        }
+        if let Some(git_dir) = self.git_dir.as_deref() {
+            command.arg(format!("--git-dir={git_dir}"));
+        }
+        if let Some(work_tree) = self.work_tree.as_deref() {
+            command.arg(format!("--work-tree={work_tree}"));
+        }
+        if self.no_replace_objects {
+            command.arg("--no-replace-objects");
+        } else {
+            // The environment variable would otherwise override an explicit
+            // --replace-objects.
+            command.env_remove("GIT_NO_REPLACE_OBJECTS");
+        }
+    }
+}
+
+/// Reads a line of git output into `buffer`, returning the length of the line
+/// without its line ending, or `None` at the end of the output. Both LF and CRLF
+/// line endings are accepted, as git wrappers on Windows may translate them.
+/// Precondition: `buffer` must be empty.
+pub fn read_line<R: BufRead>(reader: &mut R, buffer: &mut Vec<u8>) -> Option<usize> {
+    if reader.read_until(b'\n', buffer).expect("git stdout read failed") == 0 {
+        return None;
+    }
+    let line = buffer.strip_suffix(b"\n").unwrap_or(buffer);
+    Some(line.strip_suffix(b"\r").unwrap_or(line).len())
+}
+
+/// Finds git on Windows. `Command` only looks for `git.exe` in `PATH`, which
+/// misses wrappers such as `git.cmd` (found through `PATHEXT`) and Git for
+/// Windows installations that are not in `PATH`. Falls back to plain `git`.
+fn locate_git() -> PathBuf {
+    let extensions = var_os("PATHEXT")
+        .and_then(|extensions| extensions.into_string().ok())
+        .unwrap_or_else(|| ".COM;.EXE;.BAT;.CMD".into());
+    let installs = ["ProgramW6432", "ProgramFiles", "ProgramFiles(x86)"]
+        .into_iter()
+        .filter_map(var_os)
+        .map(|dir| PathBuf::from(dir).join(r"Git\cmd\git.exe"))
+        .chain(
+            var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join(r"Programs\Git\cmd\git.exe")),
+        );
+    find_git(var_os("PATH"), &extensions, installs)
+}
+
+/// Returns the first `git<extension>` file in the directories in `path`, trying
+/// the `extensions` (in `PATHEXT` form) in order in each directory, then the
+/// first of `installs` that exists, then plain `git`.
+fn find_git(
+    path: Option<OsString>,
+    extensions: &str,
+    installs: impl Iterator<Item = PathBuf>,
+) -> PathBuf {
+    let in_path = path.into_iter().flat_map(|path| split_paths(&path).collect::<Vec<_>>());
+    let candidates = in_path.flat_map(|dir| {
+        extensions
+            .split(';')
+            .filter(|extension| !extension.is_empty())
+            .map(move |extension| dir.join(format!("git{extension}")))
+            .collect::<Vec<_>>()
+    });
+    candidates.chain(installs).find(|candidate| candidate.is_file()).unwrap_or_else(|| "git".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_git, read_line};
+    use std::env::{join_paths, temp_dir};
+    use std::fs::{create_dir_all, remove_dir_all, write};
+    use std::io::Cursor;
+    use std::path::PathBuf;
+    use std::process::id;
+
+    /// Creates an empty scratch directory containing the files `names`.
+    fn scratch(test: &str, names: &[&str]) -> PathBuf {
+        let dir = temp_dir().join(format!("git-tree-test-{}-{test}", id()));
+        drop(remove_dir_all(&dir));
+        create_dir_all(&dir).unwrap();
+        for name in names {
+            write(dir.join(name), "").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn find_git_uses_pathext() {
+        let empty = scratch("pathext-empty", &[]);
+        let dir = scratch("pathext", &["git.BAT", "git.CMD"]);
+        let path = join_paths([&empty, &dir]).unwrap();
+        assert_eq!(
+            find_git(Some(path.clone()), ".EXE;.CMD;.BAT", [].into_iter()),
+            dir.join("git.CMD")
+        );
+        assert_eq!(find_git(Some(path), ".exe;;.BAT", [].into_iter()), dir.join("git.BAT"));
+        remove_dir_all(empty).unwrap();
+        remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn find_git_falls_back() {
+        let dir = scratch("fallback", &["git.exe"]);
+        let install = dir.join("git.exe");
+        let missing = dir.join("missing.exe");
+        let installs = [missing.clone(), install.clone()];
+        assert_eq!(find_git(None, ".EXE", installs.into_iter()), install);
+        assert_eq!(find_git(None, ".EXE", [missing].into_iter()), PathBuf::from("git"));
+        remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn read_line_strips_line_endings() {
+        let mut reader = Cursor::new("lf\ncrlf\r\n\r\nlast");
+        let mut lines = vec![];
+        let mut buffer = vec![];
+        while let Some(len) = read_line(&mut reader, &mut buffer) {
+            lines.push(String::from_utf8(buffer.get(..len).unwrap().to_vec()).unwrap());
+            buffer.clear();
+        }
+        assert_eq!(lines, ["lf", "crlf", "", "last"]);
+    }
+}
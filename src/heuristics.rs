@@ -0,0 +1,72 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::git_runner::GitRunner;
+use crate::interesting_branches::{exclusion, interesting_branches, BranchFilter};
+use core::iter::once;
+
+/// Which heuristic chooses the interesting branches (from --mode).
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Profile {
+    /// The current branch, its upstream, and the default branch (which
+    /// `trunk` adds).
+    Minimal,
+
+    /// The branches `interesting_branches` chooses.
+    #[default]
+    Default,
+
+    /// Every local and remote branch, and every tag. The stash entries are
+    /// interesting too (see `Selection::compute`).
+    Full,
+}
+
+/// Returns the branches `filter.profile` finds interesting, before the
+/// adjustments every profile shares (--hide-merged, the default branch, and
+/// the recently checked-out refs).
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn heuristic(git: &GitRunner, buffer: &mut Vec<u8>, filter: &BranchFilter) -> Vec<String> {
+    match filter.profile {
+        Profile::Minimal => minimal(git, filter),
+        Profile::Default => interesting_branches(git, buffer, filter),
+        Profile::Full => {
+            let everything = BranchFilter { all_remotes: true, ..filter.clone() };
+            let mut branches = interesting_branches(git, buffer, &everything);
+            branches.extend(tags(git, filter));
+            branches
+        }
+    }
+}
+
+/// Returns the current branch and its upstream, unless they are excluded.
+fn minimal(git: &GitRunner, filter: &BranchFilter) -> Vec<String> {
+    let is_excluded = exclusion(git, filter);
+    let Some(current) = git.query("symbolic-ref", &["--quiet", "--short", "HEAD"]) else {
+        return vec![];
+    };
+    let upstream = git
+        .query("for-each-ref", &["--format=%(upstream:short)", &format!("refs/heads/{current}")])
+        .filter(|upstream| !upstream.is_empty());
+    once(current).chain(upstream).filter(|name| !is_excluded(name.as_bytes())).collect()
+}
+
+/// Returns every tag, as `tags/<name>` so that it is not mistaken for a branch
+/// of the same name, unless it is excluded.
+fn tags(git: &GitRunner, filter: &BranchFilter) -> Vec<String> {
+    let is_excluded = exclusion(git, filter);
+    let listing = git
+        .query("for-each-ref", &["--format=%(refname:lstrip=1)", "refs/tags"])
+        .unwrap_or_default();
+    listing.lines().filter(|name| !is_excluded(name.as_bytes())).map(Into::into).collect()
+}
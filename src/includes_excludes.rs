@@ -13,18 +13,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::git_runner::{read_line, GitRunner};
 use core::iter::{once, repeat_n};
 use std::collections::HashMap;
-use std::io::{BufRead as _, BufReader};
-use std::process::{Command, Stdio};
+use std::io::BufReader;
+use std::process::Stdio;
 
 /// Computes the include and exclude lists to pass to git. The first list
-/// returned is the inclusion list, the second is the exclusion list.
-/// Precondition: buffer is empty.
+/// returned is the inclusion list, the second is the exclusion list. `head`
+/// (usually HEAD), if given, is one of the interesting commits.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+#[allow(clippy::too_many_lines, reason = "the traversal is easier to follow in one place")]
 pub fn includes_excludes(
-    mut buffer: Vec<u8>,
-    interesting_branches: Vec<String>,
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    interesting_branches: &Vec<String>,
     merge_bases: &Vec<String>,
+    head: Option<&str>,
 ) -> (Vec<String>, Vec<String>) {
     // We want to show the interesting commits, merge bases, and the commits on
     // a path between the two. That is equivalent to showing all commits which
@@ -79,8 +85,10 @@ pub fn includes_excludes(
         }
     }
 
-    let mut git = Command::new("git")
-        .args(["rev-list", "--parents", "--reverse", "--topo-order", "HEAD"])
+    let mut git = git
+        .internal("rev-list")
+        .args(["--parents", "--reverse", "--topo-order"])
+        .args(head)
         .args(interesting_branches)
         .arg("--not")
         .args(merge_bases)
@@ -96,9 +104,7 @@ pub fn includes_excludes(
     // each parent of this commit.
     let mut parents = Vec::with_capacity(2);
     let mut reader = BufReader::new(git.stdout.as_mut().unwrap());
-    while let Some(len) =
-        reader.read_until(b'\n', &mut buffer).expect("git stdout read failed").checked_sub(1)
-    {
+    while let Some(len) = read_line(&mut reader, buffer) {
         // Construct an iterator over the indexes of the returned commit IDs.
         // The first ID is the ID of this commit, the rest are this commit's
         // parents.
@@ -161,7 +167,6 @@ pub fn includes_excludes(
     drop(reader);
     drop(parents);
     drop(free_slots);
-    drop(buffer);
     let mut includes = vec![];
     let mut excludes = vec![];
     for (id, idx) in node_lookup {
@@ -13,49 +13,480 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
-use std::io::{BufRead as _, BufReader};
-use std::process::{Command, Stdio};
+use crate::decorations::glob_matches;
+use crate::git_runner::GitRunner;
+use crate::heuristics::Profile;
+use crate::trunk::default_branch;
+use core::iter::once;
+use std::collections::{HashMap, HashSet};
+use std::io::{stderr, Read as _, Write as _};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Which branches are interesting, from the command line.
+#[allow(clippy::struct_excessive_bools, reason = "each bool is an independent flag")]
+#[derive(Clone)]
+pub struct BranchFilter {
+    /// Refs passed to --include-ref, which are interesting regardless of the
+    /// heuristic.
+    pub include: Vec<String>,
+
+    /// Positive revisions passed to `git log`. Those that name commits are
+    /// interesting regardless of the heuristic too.
+    pub revisions: Vec<String>,
+
+    /// Whether --stash was passed, which makes every stash entry interesting.
+    pub stash: bool,
+
+    /// Whether --review-refs was passed, which makes the fetched code review
+    /// refs interesting.
+    pub review: bool,
+
+    /// False if --no-remotes was passed.
+    pub remotes: bool,
+
+    /// Remotes passed to --remote. If not empty, only these remotes' branches
+    /// are considered.
+    pub only_remotes: Vec<String>,
+
+    /// Whether --all-remotes was passed, which makes every remote branch
+    /// interesting, rather than only those with the same name as a local
+    /// branch or that a local branch tracks or pushes to.
+    pub all_remotes: bool,
+
+    /// Patterns passed to --exclude-branch.
+    pub exclude: Vec<String>,
+
+    /// Patterns passed to --only-branch. If not empty, only branches matching
+    /// one of them are interesting.
+    pub only: Vec<String>,
+
+    /// How many of HEAD's reflog entries to scan for recently checked-out
+    /// branches when HEAD is detached away from every interesting branch
+    /// (from --recent).
+    pub recent: usize,
+
+    /// The --branch-since duration, in seconds. If set, branches whose tips
+    /// were committed longer ago than this are not interesting.
+    pub max_age: Option<u64>,
+
+    /// Patterns passed to --branch-author. If not empty, only branches whose
+    /// tips were authored by someone matching one of them are interesting.
+    pub authors: Vec<String>,
+
+    /// The --max-branches limit. If set, only this many of the most recently
+    /// committed-to branches are interesting.
+    pub max_branches: Option<usize>,
+
+    /// The --hide-merged base, if it was passed: the branches already merged
+    /// into it are not interesting. Empty for the default branch.
+    pub hide_merged: Option<String>,
+
+    /// The --remote-view remote, if it was passed. Then the interesting
+    /// branches are all of that remote's branches, and no local ones (nor
+    /// HEAD).
+    pub remote_view: Option<String>,
+
+    /// Whether --mine was passed, which adds the user's `user.email` to
+    /// `authors`.
+    pub mine: bool,
+
+    /// The --head ref, which stands in for HEAD when computing the merge bases.
+    pub head: Option<String>,
+
+    /// Whether --pull-requests was passed, which fetches the pull requests
+    /// opened from the interesting branches and makes them interesting too.
+    pub pull_requests: bool,
+
+    /// Refs passed to --base, which are used as the merge bases instead of
+    /// computing them.
+    pub bases: Vec<String>,
+
+    /// Whether --merge-base-mode=pairwise was passed, which computes the merge
+    /// bases with `pairwise_merge_bases` rather than as octopus merge bases.
+    pub pairwise: bool,
+
+    /// The heuristic --mode selects.
+    pub profile: Profile,
+}
 
 /// Returns all interesting branches. Note that some commits may be in the list
-/// multiple times under different names.
-/// Precondition: `buffer` must be empty
-/// Postcondition: `buffer` will be empty
-pub fn interesting_branches(buffer: &mut Vec<u8>) -> Vec<String> {
-    // This considers a branch interesting if it is a local branch or if it has
-    // the same name as a local branch.
-    let mut git = Command::new("git")
-        .args(["branch", "-a", "--format=%(refname)"])
-        .stdout(Stdio::piped())
-        .spawn()
-        .expect("failed to run git");
+/// multiple times under different names. Branches matching an --exclude-branch
+/// or `tree.excludeBranch` pattern are left out, as are remote branches if
+/// --no-remotes was passed or `tree.remotes` is false, and the branches of
+/// remotes not passed to --remote (if it was). If there are --only-branch
+/// patterns, branches that match none of them are left out too, and so are
+/// branches older than --branch-since or by authors other than those given to
+/// --branch-author (or --mine). With --max-branches, only the most recently
+/// committed-to branches are kept. With --remote-view, the remote's branches
+/// are interesting instead.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn interesting_branches(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    filter: &BranchFilter,
+) -> Vec<String> {
+    // This considers a branch interesting if it is a local branch, if it has
+    // the same name as a local branch, or if it is a local branch's upstream
+    // (from `branch.<name>.remote` and `branch.<name>.merge`) or push
+    // destination (which differs from the upstream in triangular workflows,
+    // per `branch.<name>.pushRemote` or `remote.pushDefault`).
+    let mut child = git.spawn_watched(git.internal("branch").args([
+        "-a",
+        "--no-column",
+        "--format=%(refname)%00%(symref)%00%(committerdate:unix)%00%(authorname) %(authoremail)\
+          %00%(upstream)%00%(push)",
+    ]));
+    // Repositories can have tens of thousands of remote branches, so the
+    // output is read whole and parsed in place, and only the interesting
+    // branches are copied out of it.
+    child.stdout().read_to_end(buffer).expect("git stdout read failed");
+    child.finish();
+    let is_excluded = exclusion(git, filter);
+    let is_chosen = |name: &[u8]| is_chosen(filter, name);
+    let use_remotes = uses_remotes(git, filter);
+    let is_wanted_remote = |remote: &[u8]| is_wanted_remote(filter, remote);
+    let is_wanted_tip = tip_filter(git, filter);
+    let lines = buffer.split(|&b| b == b'\n').map(|line| line.strip_suffix(b"\r").unwrap_or(line));
     let mut locals = HashSet::new();
+    // Map from remote branch to the local branch it is the upstream or push
+    // destination of.
+    let mut tracked = HashMap::new();
     let mut remotes = vec![];
-    let mut reader = BufReader::new(git.stdout.as_mut().unwrap());
-    while let Some(len) =
-        reader.read_until(b'\n', buffer).expect("git stdout read failed").checked_sub(1)
-    {
-        if buffer.first_chunk() == Some(b"refs/remotes/") {
-            remotes.push(buffer.get(b"refs/remotes/".len()..len).unwrap().to_vec());
-        } else if buffer.first_chunk() == Some(b"refs/heads/") {
-            locals.insert(buffer.get(b"refs/heads/".len()..len).unwrap().into());
+    // Local branches whose tips are too old (or by someone else) for them to
+    // be interesting themselves, but that still pair remote branches.
+    let mut unwanted = HashSet::new();
+    // Tip committer dates, for --max-branches.
+    let mut dates = HashMap::new();
+    for line in lines {
+        let mut fields = line.split(|&b| b == 0);
+        let refname = fields.next().unwrap_or_default();
+        // Symbolic refs, such as `origin/HEAD` (listed by `git branch -a` as
+        // `origin/HEAD -> origin/main`), are skipped: they only duplicate the
+        // branch they point to, and `HEAD` is included anyway.
+        if !fields.next().unwrap_or_default().is_empty() {
+            continue;
         }
-        buffer.clear();
-    }
-    drop(reader);
-    let mut interesting = vec![];
-    for remote in remotes {
-        let Some(idx) = remote.iter().position(|&b| b == b'/') else { continue };
-        #[allow(clippy::arithmetic_side_effects, reason = "idx is less than buffer.len()")]
-        let (_, name) = remote.split_at(idx + 1);
-        if locals.contains(name) {
-            interesting.push(String::from_utf8(remote).expect("non-utf-8 branch"));
+        let (date, author) = (fields.next().unwrap_or_default(), fields.next());
+        let wanted_tip = is_wanted_tip(date, author.unwrap_or_default());
+        let short_name =
+            refname.strip_prefix(b"refs/heads/").or_else(|| refname.strip_prefix(b"refs/remotes/"));
+        if let (Some(name), Ok(date)) = (short_name, String::from_utf8_lossy(date).parse::<u64>()) {
+            dates.insert(name, date);
+        }
+        // An excluded local branch also takes the remote branches that share
+        // its name, its upstream, and its push destination out of the
+        // selection.
+        if let Some(remote) = refname.strip_prefix(b"refs/remotes/") {
+            if use_remotes && wanted_tip && is_wanted_remote(remote) && !is_excluded(remote) {
+                remotes.push(remote);
+            }
+        } else if let Some(local) = refname.strip_prefix(b"refs/heads/") {
+            if !is_excluded(local) {
+                locals.insert(local);
+                if !wanted_tip {
+                    unwanted.insert(local);
+                }
+                // The remaining fields are the upstream and push destination.
+                for remote in fields.filter_map(|target| target.strip_prefix(b"refs/remotes/")) {
+                    tracked.insert(remote, local);
+                }
+            }
         }
     }
+    // A remote branch is chosen by --only-branch if its own name, its name on
+    // the remote, or the name of the local branch tracking (or pushing to) it
+    // matches.
+    let mut interesting: Vec<_> = remotes
+        .into_iter()
+        .filter(|remote| {
+            remote.iter().position(|&b| b == b'/').is_some_and(|idx| {
+                #[allow(clippy::arithmetic_side_effects, reason = "idx is less than remote.len()")]
+                let (_, name) = remote.split_at(idx + 1);
+                let local = tracked.get(remote);
+                let paired = filter.all_remotes
+                    || filter.remote_view.is_some()
+                    || locals.contains(name)
+                    || local.is_some();
+                paired
+                    && (is_chosen(remote)
+                        || is_chosen(name)
+                        || local.is_some_and(|local| is_chosen(local)))
+            })
+        })
+        .map(|remote| String::from_utf8(remote.to_vec()).expect("non-utf-8 branch"))
+        .collect();
     interesting.extend(
-        locals.into_iter().map(|local| String::from_utf8(local).expect("non-utf-8 branch")),
+        locals
+            .into_iter()
+            .filter(|local| {
+                filter.remote_view.is_none() && !unwanted.contains(local) && is_chosen(local)
+            })
+            .map(|local| String::from_utf8(local.to_vec()).expect("non-utf-8 branch")),
     );
-    let status = git.wait().expect("failed to wait for git");
-    assert!(status.success(), "git returned unsuccessful status {status}");
+    if let Some(max) = filter.max_branches {
+        cap(&mut interesting, &dates, max);
+    }
+    buffer.clear();
     interesting
 }
+
+/// Implements --max-branches: keeps the `max` most recently committed-to of
+/// `branches` (per `dates`, their tips' committer dates) and says on stderr
+/// how many were left out, so the output is not mistaken for everything.
+fn cap(branches: &mut Vec<String>, dates: &HashMap<&[u8], u64>, max: usize) {
+    if branches.len() <= max {
+        return;
+    }
+    let date = |branch: &String| dates.get(branch.as_bytes()).copied().unwrap_or_default();
+    branches.sort_by_key(|branch| u64::MAX.saturating_sub(date(branch)));
+    let omitted = branches.split_off(max).len();
+    let plural = if omitted == 1 { "" } else { "es" };
+    writeln!(stderr().lock(), "git-tree: omitted {omitted} older branch{plural} (--max-branches)")
+        .expect("stderr write failed");
+}
+
+/// Returns a predicate for whether a branch tip, given its committer date (as
+/// a Unix timestamp) and `Name <email>` author, passes --branch-since and
+/// --branch-author (or --mine). Author patterns match any part of the author,
+/// ignoring case.
+fn tip_filter<'run>(
+    git: &GitRunner,
+    filter: &'run BranchFilter,
+) -> impl Fn(&[u8], &[u8]) -> bool + 'run {
+    let cutoff = filter.max_age.map(|max_age| {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("clock before 1970");
+        now.as_secs().saturating_sub(max_age)
+    });
+    let mut authors: Vec<_> = filter.authors.iter().map(|author| author.to_lowercase()).collect();
+    if filter.mine {
+        let email = git.query("config", &["--get", "user.email"]);
+        assert!(email.is_some(), "--mine requires user.email to be set");
+        authors.push(format!("<{}>", email.unwrap().to_lowercase()));
+    }
+    move |date, author| {
+        let date = str::from_utf8(date).ok().and_then(|date| date.parse::<u64>().ok());
+        let author = String::from_utf8_lossy(author).to_lowercase();
+        cutoff.is_none_or(|cutoff| date.is_some_and(|date| date >= cutoff))
+            && (authors.is_empty() || authors.iter().any(|pattern| author.contains(pattern)))
+    }
+}
+
+/// Returns refs that HEAD recently visited, from the last `filter.recent`
+/// entries of its reflog, if HEAD is detached and not reachable from any of
+/// the `interesting` branches. Otherwise, the output would show the detached
+/// history alone, with nothing around it. Only names that are still refs (such
+/// as a remote branch or tag that was checked out) and are not already
+/// interesting or excluded are returned.
+pub fn recent_branches(
+    git: &GitRunner,
+    filter: &BranchFilter,
+    interesting: &[String],
+) -> Vec<String> {
+    if filter.recent == 0 || git.query("symbolic-ref", &["--quiet", "HEAD"]).is_some() {
+        return vec![];
+    }
+    let containing = git
+        .query(
+            "for-each-ref",
+            &["--contains=HEAD", "--format=%(refname:lstrip=2)", "refs/heads", "refs/remotes"],
+        )
+        .unwrap_or_default();
+    if containing.lines().any(|name| interesting.iter().any(|branch| branch == name)) {
+        return vec![];
+    }
+    let reflog = git
+        .query("reflog", &["show", &format!("-n{}", filter.recent), "--format=%gs", "HEAD", "--"])
+        .unwrap_or_default();
+    let is_excluded = exclusion(git, filter);
+    let mut recent: Vec<String> = vec![];
+    let checkouts = reflog
+        .lines()
+        .filter_map(|action| action.strip_prefix("checkout: moving from "))
+        .filter_map(|moved| moved.split_once(" to "));
+    for name in checkouts.flat_map(|moved| [moved.1, moved.0]) {
+        // Commit IDs have no full name, and deleted branches do not resolve.
+        let is_ref = git
+            .query("rev-parse", &["--symbolic-full-name", name, "--"])
+            .is_some_and(|full| full.lines().next().is_some_and(|full| full.starts_with("refs/")));
+        if is_ref
+            && !is_excluded(name.as_bytes())
+            && !interesting.iter().chain(&recent).any(|branch| branch == name)
+        {
+            recent.push(name.into());
+        }
+    }
+    recent
+}
+
+/// Returns the default branch if it should be added to `branches`: so that the
+/// tree is always rooted at the mainline, it is interesting even without a
+/// local copy, unless it is already interesting, does not exist, or is left out
+/// by `filter` the way any other branch would be. Like the rest of
+/// --mode=minimal's branches, it is only subject to the exclusions then.
+pub fn trunk(git: &GitRunner, filter: &BranchFilter, branches: &[String]) -> Option<String> {
+    default_branch(git).filter(|trunk| {
+        let is_ref = |prefix: &str| {
+            git.query("rev-parse", &["--verify", "--quiet", &format!("{prefix}{trunk}^{{commit}}")])
+                .is_some()
+        };
+        // The default branch is usually a remote branch (such as
+        // `origin/main`), which is chosen by --only-branch by its name on
+        // the remote too.
+        let wanted = if filter.profile == Profile::Minimal {
+            true
+        } else if is_ref("refs/remotes/") {
+            let name = trunk.split_once('/').map_or(trunk.as_str(), |(_, name)| name);
+            uses_remotes(git, filter)
+                && is_wanted_remote(filter, trunk.as_bytes())
+                && (is_chosen(filter, trunk.as_bytes()) || is_chosen(filter, name.as_bytes()))
+        } else {
+            filter.remote_view.is_none() && is_chosen(filter, trunk.as_bytes())
+        };
+        wanted
+            && !branches.contains(trunk)
+            && !exclusion(git, filter)(trunk.as_bytes())
+            && is_ref("")
+    })
+}
+
+/// Implements --hide-merged: removes the branches that are fully merged into
+/// the base (other than the base itself) from `branches`. They add no commits
+/// to the output, but still clutter the decorations and hold the merge bases
+/// down.
+pub fn hide_merged(git: &GitRunner, filter: &BranchFilter, branches: &mut Vec<String>) {
+    let Some(base) = filter.hide_merged.as_deref() else { return };
+    let base = if base.is_empty() {
+        let trunk = default_branch(git);
+        assert!(trunk.is_some(), "--hide-merged: cannot determine the default branch");
+        trunk.unwrap()
+    } else {
+        base.into()
+    };
+    let merged = git
+        .query("branch", &["-a", "--no-column", "--format=%(refname:lstrip=2)", "--merged", &base]);
+    assert!(merged.is_some(), "--hide-merged: {base} is not a commit");
+    let merged: HashSet<_> = merged.as_deref().unwrap_or_default().lines().collect();
+    branches.retain(|branch| *branch == base || !merged.contains(branch.as_str()));
+}
+
+/// Returns the refs in the namespaces listed in `tree.refNamespaces` (e.g.
+/// `refs/wip/*`), by their full names so they cannot be mistaken for branches.
+/// Refs matching an --exclude-branch or `tree.excludeBranch` pattern are left
+/// out.
+pub fn namespace_refs(git: &GitRunner, filter: &BranchFilter) -> Vec<String> {
+    let namespaces = git.settings().get_all("tree.refNamespaces");
+    if namespaces.is_empty() {
+        return vec![];
+    }
+    let is_excluded = exclusion(git, filter);
+    let args: Vec<_> = once("--format=%(refname)").chain(namespaces).collect();
+    let listing = git.query("for-each-ref", &args).unwrap_or_default();
+    listing.lines().filter(|name| !is_excluded(name.as_bytes())).map(Into::into).collect()
+}
+
+/// Returns the code review refs that have been fetched into the repository:
+/// GitHub's `refs/pull/<n>/head`, and Gerrit's `refs/changes/*` (only the
+/// newest patch set of each change) and `refs/for/*`. They are named without
+/// their `refs/` prefix (e.g. `pull/12/head`), which git still resolves. Refs
+/// matching an --exclude-branch or `tree.excludeBranch` pattern are left out.
+pub fn review_refs(git: &GitRunner, filter: &BranchFilter) -> Vec<String> {
+    let listing = git
+        .query(
+            "for-each-ref",
+            &["--format=%(refname:lstrip=1)", "refs/pull/*/head", "refs/changes", "refs/for"],
+        )
+        .unwrap_or_default();
+    let is_excluded = exclusion(git, filter);
+    // Gerrit names patch sets changes/<last two digits>/<change>/<patch set>.
+    let mut newest: HashMap<&str, (u64, &str)> = HashMap::new();
+    let mut refs = vec![];
+    for name in listing.lines().filter(|name| !is_excluded(name.as_bytes())) {
+        let patch_set = name
+            .strip_prefix("changes/")
+            .and_then(|change| change.rsplit_once('/'))
+            .and_then(|(change, number)| number.parse().ok().map(|number: u64| (change, number)));
+        match patch_set {
+            Some((change, number)) => {
+                let entry = newest.entry(change).or_insert((number, name));
+                if entry.0 < number {
+                    *entry = (number, name);
+                }
+            }
+            None => refs.push(name.to_owned()),
+        }
+    }
+    let mut changes: Vec<_> = newest.into_values().map(|newest| newest.1.to_owned()).collect();
+    changes.sort_unstable();
+    refs.extend(changes);
+    refs
+}
+
+/// Returns whether a branch name matches an --exclude-branch or
+/// `tree.excludeBranch` pattern.
+pub fn exclusion<'run>(
+    git: &'run GitRunner,
+    filter: &'run BranchFilter,
+) -> impl Fn(&[u8]) -> bool + 'run {
+    let excluded = git.settings().get_all("tree.excludeBranch");
+    move |name| {
+        excluded
+            .iter()
+            .copied()
+            .chain(filter.exclude.iter().map(String::as_str))
+            .any(|pattern| glob_matches(pattern.as_bytes(), name))
+    }
+}
+
+/// Returns whether `name` is chosen by --only-branch (every branch is, if it
+/// was not passed).
+fn is_chosen(filter: &BranchFilter, name: &[u8]) -> bool {
+    filter.only.is_empty()
+        || filter.only.iter().any(|pattern| glob_matches(pattern.as_bytes(), name))
+}
+
+/// Returns whether remote branches are considered at all, per --no-remotes and
+/// `tree.remotes` (which --remote-view overrides).
+fn uses_remotes(git: &GitRunner, filter: &BranchFilter) -> bool {
+    filter.remote_view.is_some()
+        || filter.remotes && git.settings().get_bool("tree.remotes") != Some(false)
+}
+
+/// Returns whether the remote branch `remote` (e.g. `origin/main`) is on a
+/// remote that --remote or --remote-view asks for.
+fn is_wanted_remote(filter: &BranchFilter, remote: &[u8]) -> bool {
+    let on_remote = |name: &str| {
+        remote.strip_prefix(name.as_bytes()).is_some_and(|rest| rest.starts_with(b"/"))
+    };
+    filter.remote_view.as_deref().map_or_else(
+        || filter.only_remotes.is_empty() || filter.only_remotes.iter().any(|name| on_remote(name)),
+        on_remote,
+    )
+}
+
+/// Sorts `branches` the way `git branch` lists them: by `branch.sort` if it is
+/// set (e.g. `-committerdate`), and by name (local branches first) otherwise.
+/// Names that are not branches (such as tags from --refs-from-stdin) go last.
+pub fn sort(git: &GitRunner, branches: &mut [String]) {
+    let key = git.query("config", &["--get", "branch.sort"]);
+    let listing = git
+        .query(
+            "for-each-ref",
+            &[
+                &format!("--sort={}", key.as_deref().unwrap_or("refname")),
+                "--format=%(refname)",
+                "refs/heads",
+                "refs/remotes",
+            ],
+        )
+        .unwrap_or_default();
+    let mut ranks = HashMap::new();
+    for (rank, refname) in listing.lines().enumerate() {
+        let name =
+            refname.strip_prefix("refs/heads/").or_else(|| refname.strip_prefix("refs/remotes/"));
+        if let Some(name) = name {
+            ranks.entry(name).or_insert(rank);
+        }
+    }
+    branches.sort_by_key(|branch| ranks.get(branch.as_str()).copied().unwrap_or(usize::MAX));
+}
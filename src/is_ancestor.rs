@@ -0,0 +1,111 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commits::{branch_tips, stream_displayed_commits};
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use std::collections::{HashMap, HashSet};
+use std::io::{stderr, stdin, stdout, Write as _};
+use std::process::Stdio;
+use std::thread::scope;
+
+/// Implements `git-tree is-ancestor`, which reads `<commit> <ref>` pairs from
+/// standard input, one per line, and prints `yes` or `no` for each, as
+/// `git merge-base --is-ancestor <commit> <ref>` would answer. Pairs of a
+/// displayed commit and an interesting branch are answered from the displayed
+/// commits' branches, which one walk computes for every pair at once; other
+/// pairs fall back to `git merge-base`. A pair naming something that is not a
+/// commit is answered `no`, with a warning on standard error.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn is_ancestor(git: &GitRunner, buffer: &mut Vec<u8>, selection: &Selection) {
+    let pairs: Vec<(String, String)> = stdin()
+        .lines()
+        .map(|line| line.expect("stdin read failed"))
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let pair = line.split_once(char::is_whitespace);
+            assert!(pair.is_some(), "is-ancestor expects `<commit> <ref>` lines, got: {line}");
+            let (commit, refname) = pair.unwrap();
+            (commit.into(), refname.trim().into())
+        })
+        .collect();
+    let ids = resolve(git, pairs.iter().flat_map(|pair| [pair.0.as_str(), pair.1.as_str()]));
+    let tips = branch_tips(git, buffer, &selection.interesting_branches);
+    let wanted: HashSet<_> = ids.iter().step_by(2).flatten().collect();
+    let mut reachers = HashMap::new();
+    stream_displayed_commits(git, buffer, selection, |commit| {
+        if wanted.contains(&commit.id) {
+            reachers.insert(commit.id, commit.branches);
+        }
+    });
+    let mut out = stdout().lock();
+    for (commit, refname) in ids.iter().step_by(2).zip(ids.iter().skip(1).step_by(2)) {
+        let (Some(commit), Some(refname)) = (commit.as_ref(), refname.as_ref()) else {
+            writeln!(out, "no").written();
+            continue;
+        };
+        let answer = match (reachers.get(commit), tips.get(refname)) {
+            (Some(branches), Some(tip_of)) => branches.iter().any(|idx| tip_of.contains(idx)),
+            _ => git.query("merge-base", &["--is-ancestor", commit, refname]).is_some(),
+        };
+        writeln!(out, "{}", if answer { "yes" } else { "no" }).written();
+    }
+}
+
+/// Resolves each of `names` to a commit ID, in order, with a single git
+/// command. Names that are not commits resolve to `None`, after a warning.
+fn resolve<'name, I: Iterator<Item = &'name str>>(
+    git: &GitRunner,
+    names: I,
+) -> Vec<Option<String>> {
+    let names: Vec<_> = names.collect();
+    let mut child = git
+        .internal("cat-file")
+        .arg("--batch-check=%(objectname)")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run git");
+    let mut input = child.stdin.take().unwrap();
+    // The input is written from another thread so that git cannot block on a
+    // full stdout pipe while we block on a full stdin pipe.
+    let output = scope(|threads| {
+        threads.spawn(|| {
+            for name in &names {
+                writeln!(input, "{name}^{{commit}}").expect("git cat-file write failed");
+            }
+            drop(input);
+        });
+        child.wait_with_output().expect("failed to wait for git")
+    });
+    assert!(output.status.success(), "git returned unsuccessful status {}", output.status);
+    let output = String::from_utf8(output.stdout).expect("non-utf-8 git output");
+    // An unknown name is echoed back followed by " missing" (or another
+    // problem, such as " ambiguous").
+    let mut err = stderr().lock();
+    names
+        .iter()
+        .zip(output.lines())
+        .map(|(name, id)| {
+            if !id.contains(' ') {
+                return Some(id.into());
+            }
+            writeln!(err, "git-tree: is-ancestor: {name} is not a commit")
+                .expect("stderr write failed");
+            None
+        })
+        .collect()
+}
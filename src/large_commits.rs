@@ -0,0 +1,52 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::selection::Selection;
+use std::collections::HashMap;
+
+/// Returns a warning for each outstanding commit that changes more lines
+/// (insertions plus deletions) than `tree.largeCommit`, keyed by commit ID,
+/// to catch accidentally committed vendored code or build artifacts. Returns
+/// nothing if `tree.largeCommit` is not set.
+pub fn large_commits(git: &GitRunner, selection: &Selection) -> HashMap<String, String> {
+    let Some(limit) = git.settings().get("tree.largeCommit") else { return HashMap::new() };
+    let limit: u64 = limit.parse().expect("invalid tree.largeCommit");
+    let output = git
+        .internal("log")
+        .args(["--shortstat", "--format=%x00%H"])
+        .args(selection.outstanding_revisions())
+        .arg("--")
+        .output()
+        .expect("failed to run git");
+    assert!(output.status.success(), "git returned unsuccessful status {}", output.status);
+    let output = String::from_utf8_lossy(&output.stdout);
+    let mut warnings = HashMap::new();
+    // Each commit is a NUL-prefixed ID, followed (unless the commit is a merge
+    // or changes nothing) by a line like ` 2 files changed, 10 insertions(+),
+    // 3 deletions(-)`.
+    for entry in output.split('\0').filter(|entry| !entry.is_empty()) {
+        let (id, stat) = entry.split_once('\n').unwrap_or((entry, ""));
+        let changed: u64 = stat
+            .split(',')
+            .map(str::trim)
+            .filter(|part| part.contains("insertion") || part.contains("deletion"))
+            .filter_map(|part| part.split(' ').next().unwrap_or_default().parse::<u64>().ok())
+            .sum();
+        if changed > limit {
+            warnings.insert(id.into(), format!("{changed} lines changed (limit {limit})"));
+        }
+    }
+    warnings
+}
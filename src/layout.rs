@@ -0,0 +1,259 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Assigns commits to columns ("lanes") for drawing the commit graph. Each
+//! commit gets its own row, in the order the commits were loaded.
+//!
+//! Each branch's first-parent chain stays in one lane. Branches claim lanes in
+//! a fixed priority order (the default branch first, then by name), each taking
+//! the leftmost lane that is free for the rows it spans, so a branch's lane
+//! only changes when the branches before it change shape.
+
+use crate::commits::Commit;
+use crate::git_runner::GitRunner;
+use crate::selection::Selection;
+use crate::trunk::default_branch;
+use std::collections::HashMap;
+
+/// A parent-child relationship between two loaded commits.
+pub struct Edge {
+    /// Index of the child commit.
+    pub child: usize,
+
+    /// Index of the parent commit.
+    pub parent: usize,
+
+    /// The column the edge occupies in the rows between the child and the
+    /// parent.
+    pub lane: usize,
+}
+
+pub struct Layout {
+    /// Column of each commit, indexed like the commits.
+    pub columns: Vec<usize>,
+
+    pub edges: Vec<Edge>,
+
+    /// The number of columns used.
+    pub width: usize,
+}
+
+/// Lays out `commits`, which must be ordered children before parents. `tips`
+/// are the IDs of the branch tips, in the order the branches should claim
+/// lanes.
+pub fn layout(commits: &[Commit], tips: &[&str]) -> Layout {
+    let rows: HashMap<&str, usize> =
+        commits.iter().enumerate().map(|(row, commit)| (commit.id.as_str(), row)).collect();
+    let parents: Vec<Vec<usize>> = commits
+        .iter()
+        .map(|commit| {
+            commit.parents.iter().filter_map(|parent| rows.get(parent.as_str()).copied()).collect()
+        })
+        .collect();
+    let first_parents: Vec<Option<usize>> = commits
+        .iter()
+        .map(|commit| {
+            commit.parents.iter().take(1).find_map(|parent| rows.get(parent.as_str()).copied())
+        })
+        .collect();
+
+    // Split the commits into first-parent chains, starting with the branch tips
+    // (in priority order) and then any commits they do not reach.
+    let mut chain_of: Vec<Option<usize>> = vec![None; commits.len()];
+    let mut chains: Vec<Vec<usize>> = vec![];
+    let starts = tips.iter().filter_map(|tip| rows.get(tip).copied()).chain(0..commits.len());
+    for start in starts {
+        let mut chain = vec![];
+        let mut next = Some(start);
+        while let Some(row) = next.filter(|&row| chain_of.get(row).unwrap().is_none()) {
+            *chain_of.get_mut(row).unwrap() = Some(chains.len());
+            chain.push(row);
+            next = *first_parents.get(row).unwrap();
+        }
+        if !chain.is_empty() {
+            chains.push(chain);
+        }
+    }
+
+    // The rows each chain's lane is occupied for, as a half-open range: from
+    // just below the topmost merge into the chain to just above the fork point
+    // below its last commit.
+    let mut spans: Vec<(usize, usize)> = chains
+        .iter()
+        .map(|chain| {
+            let last = *chain.last().unwrap();
+            let end = first_parents.get(last).unwrap().unwrap_or_else(|| last.saturating_add(1));
+            (*chain.first().unwrap(), end)
+        })
+        .collect();
+    for (child, (child_parents, &first)) in parents.iter().zip(&first_parents).enumerate() {
+        for &parent in child_parents.iter().filter(|&&parent| Some(parent) != first) {
+            let span = spans.get_mut(chain_of.get(parent).unwrap().unwrap()).unwrap();
+            span.0 = span.0.min(child.saturating_add(1));
+        }
+    }
+
+    // Give each chain the leftmost column that is free for its whole span.
+    let mut occupied: Vec<Vec<(usize, usize)>> = vec![];
+    let lanes: Vec<usize> = spans
+        .iter()
+        .map(|&(start, end)| {
+            let free = |column: &Vec<(usize, usize)>| {
+                column
+                    .iter()
+                    .all(|&(other_start, other_end)| end <= other_start || other_end <= start)
+            };
+            let lane = occupied.iter().position(free).unwrap_or_else(|| {
+                occupied.push(vec![]);
+                occupied.len().saturating_sub(1)
+            });
+            occupied.get_mut(lane).unwrap().push((start, end));
+            lane
+        })
+        .collect();
+
+    let column = |row: usize| *lanes.get(chain_of.get(row).unwrap().unwrap()).unwrap();
+    let columns = (0..commits.len()).map(column).collect();
+    let mut edges = vec![];
+    for (child, child_parents) in parents.iter().enumerate() {
+        for &parent in child_parents {
+            // A first-parent edge stays in the child's lane until it reaches
+            // the parent; other edges (merges) travel in the parent's lane.
+            let is_first = *first_parents.get(child).unwrap() == Some(parent);
+            let lane = if is_first { column(child) } else { column(parent) };
+            edges.push(Edge { child, parent, lane });
+        }
+    }
+    Layout { columns, edges, width: occupied.len() }
+}
+
+/// Returns the branch tips in the order their branches claim lanes: the default
+/// branch (and its local counterpart) first, then the other branches by name,
+/// so each branch's lane is stable from run to run.
+pub fn lane_order<'tips>(
+    git: &GitRunner,
+    selection: &Selection,
+    tips: &'tips HashMap<String, Vec<usize>>,
+) -> Vec<&'tips str> {
+    let default = default_branch(git);
+    let local_default = default.as_deref().and_then(|default| {
+        default
+            .split_once('/')
+            .map(|(_, name)| name)
+            .filter(|name| selection.interesting_branches.iter().any(|branch| branch == name))
+    });
+    let mut branches: Vec<(usize, &str, &str)> = tips
+        .iter()
+        .flat_map(|(id, indexes)| indexes.iter().map(move |&idx| (idx, id.as_str())))
+        .map(|(idx, id)| {
+            let name = selection.interesting_branches.get(idx).unwrap().as_str();
+            let rank = if Some(name) == local_default {
+                0
+            } else if Some(name) == default.as_deref() {
+                1
+            } else {
+                2
+            };
+            (rank, name, id)
+        })
+        .collect();
+    branches.sort_unstable();
+    branches.into_iter().map(|(_, _, id)| id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{layout, Layout};
+    use crate::commits::Commit;
+
+    /// Returns a commit with ID `id` and `parents`, and no other details.
+    fn commit(id: &str, parents: &[&str]) -> Commit {
+        Commit {
+            id: id.into(),
+            short_id: id.into(),
+            parents: parents.iter().map(|&parent| parent.into()).collect(),
+            author: String::new(),
+            timestamp: 0,
+            date: String::new(),
+            subject: String::new(),
+            title: String::new(),
+            branches: vec![],
+        }
+    }
+
+    /// Returns the (child, parent, lane) triple of each edge in `layout`.
+    fn edges(layout: &Layout) -> Vec<(usize, usize, usize)> {
+        layout.edges.iter().map(|edge| (edge.child, edge.parent, edge.lane)).collect()
+    }
+
+    #[test]
+    fn linear_history_uses_one_lane() {
+        let commits = [commit("c", &["b"]), commit("b", &["a"]), commit("a", &[])];
+        let laid_out = layout(&commits, &["c"]);
+        assert_eq!(laid_out.columns, [0, 0, 0]);
+        assert_eq!(edges(&laid_out), [(0, 1, 0), (1, 2, 0)]);
+        assert_eq!(laid_out.width, 1);
+    }
+
+    #[test]
+    fn branches_claim_lanes_in_order() {
+        // main: m -> base; topic: t -> base.
+        let commits = [commit("t", &["base"]), commit("m", &["base"]), commit("base", &[])];
+        let main_first = layout(&commits, &["m", "t"]);
+        assert_eq!(main_first.columns, [1, 0, 0]);
+        assert_eq!(main_first.width, 2);
+        let topic_first = layout(&commits, &["t", "m"]);
+        assert_eq!(topic_first.columns, [0, 1, 0]);
+    }
+
+    #[test]
+    fn merges_travel_in_the_parent_lane() {
+        // main merges topic: merge -> (m, t), both -> base.
+        let commits = [
+            commit("merge", &["m", "t"]),
+            commit("t", &["base"]),
+            commit("m", &["base"]),
+            commit("base", &[]),
+        ];
+        let laid_out = layout(&commits, &["merge"]);
+        assert_eq!(laid_out.columns, [0, 1, 0, 0]);
+        assert_eq!(edges(&laid_out), [(0, 2, 0), (0, 1, 1), (1, 3, 1), (2, 3, 0)]);
+        assert_eq!(laid_out.width, 2);
+    }
+
+    #[test]
+    fn lanes_are_reused_once_free() {
+        // Two side branches forked off main at different points, one above the
+        // other, share a lane.
+        let commits = [
+            commit("m3", &["m2"]),
+            commit("x", &["m2"]),
+            commit("m2", &["m1"]),
+            commit("y", &["m0"]),
+            commit("m1", &["m0"]),
+            commit("m0", &[]),
+        ];
+        let laid_out = layout(&commits, &["m3", "x", "y"]);
+        assert_eq!(laid_out.columns, [0, 1, 0, 1, 0, 0]);
+        assert_eq!(laid_out.width, 2);
+    }
+
+    #[test]
+    fn unloaded_parents_are_ignored() {
+        let commits = [commit("b", &["a", "outside"]), commit("a", &["older"])];
+        let laid_out = layout(&commits, &[]);
+        assert_eq!(laid_out.columns, [0, 0]);
+        assert_eq!(edges(&laid_out), [(0, 1, 0)]);
+    }
+}
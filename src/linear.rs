@@ -0,0 +1,115 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::branch_stats::branch_stats;
+use crate::commits::{base_of, branch_tips, merge_base_commits, outstanding_commits, Commit};
+use crate::dependencies::dependencies;
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::protection::Protection;
+use crate::selection::Selection;
+use std::collections::HashMap;
+use std::io::{stdout, Write as _};
+
+/// Implements `--linear`, which describes the displayed commits in words, one
+/// self-contained line per branch, commit, and merge base, instead of drawing
+/// a graph. This suits screen readers and grep. Unless `no_stats` (from
+/// --no-stats) is set, each branch's line ends with how far it is from its
+/// upstream. Branches' lines also list the branches they depend on.
+/// Precondition: `buffer` must be empty.
+pub fn linear(git: &GitRunner, buffer: &mut Vec<u8>, selection: &Selection, no_stats: bool) {
+    let commits = outstanding_commits(git, buffer, selection);
+    let merge_bases = merge_base_commits(git, buffer, selection);
+    let tips = branch_tips(git, buffer, &selection.interesting_branches);
+    let short_ids: HashMap<&str, &str> = commits
+        .iter()
+        .chain(&merge_bases)
+        .map(|commit| (commit.id.as_str(), commit.short_id.as_str()))
+        .collect();
+    let short = |id: &str| short_ids.get(id).copied().unwrap_or(id).to_owned();
+    let names = |commit: &Commit| {
+        let mut names: Vec<_> = commit
+            .branches
+            .iter()
+            .map(|&idx| selection.interesting_branches.get(idx).unwrap().as_str())
+            .collect();
+        names.sort_unstable();
+        names.join(", ")
+    };
+    let protection = Protection::load(git);
+    let stats = branch_stats(git, selection, no_stats);
+    let dependencies = dependencies(git, selection);
+    let mut out = stdout().lock();
+    for (idx, name) in selection.interesting_branches.iter().enumerate() {
+        let Some(tip) = tips.iter().find(|tip| tip.1.contains(&idx)).map(|tip| tip.0.as_str())
+        else {
+            continue;
+        };
+        let own: Vec<_> =
+            commits.iter().filter(|commit| commit.branches.binary_search(&idx).is_ok()).collect();
+        // The merge bases this branch's oldest commits (or its tip) sit on.
+        let mut bases: Vec<_> = own
+            .iter()
+            .flat_map(|commit| &commit.parents)
+            .map(String::as_str)
+            .chain(own.is_empty().then_some(tip))
+            .filter(|&id| selection.merge_bases.iter().any(|base| base == id))
+            .map(short)
+            .collect();
+        bases.sort_unstable();
+        bases.dedup();
+        let bases = if bases.is_empty() { "no merge base".into() } else { bases.join(", ") };
+        let count = own.len();
+        let protected = if protection.covers(name) { " (protected)" } else { "" };
+        let position = if count == 0 {
+            format!("at merge base {bases}")
+        } else {
+            let plural = if count == 1 { "" } else { "s" };
+            format!("{count} commit{plural} ahead of merge base {bases}")
+        };
+        let stats = stats.get(name).map(|stats| format!(" ({stats})")).unwrap_or_default();
+        let depends = dependencies
+            .get(name)
+            .map(|depends| format!(", depends on {}", depends.join(", ")))
+            .unwrap_or_default();
+        writeln!(out, "branch {name}{protected} at {}: {position}{depends}{stats}", short(tip))
+            .written();
+    }
+    for commit in &commits {
+        let parents: Vec<_> = commit.parents.iter().map(|parent| short(parent)).collect();
+        let relation = match (parents.first(), parents.get(1..).unwrap_or_default()) {
+            (None, _) => "root commit".into(),
+            (Some(parent), &[]) => format!("child of {parent}"),
+            (Some(first), rest) => format!("merges {} into {first}", rest.join(", ")),
+        };
+        writeln!(
+            out,
+            "commit {} on {}, {relation}, by {} on {}: {}",
+            commit.short_id,
+            names(commit),
+            commit.author,
+            commit.date,
+            commit.title
+        )
+        .written();
+    }
+    let base_of = base_of(git, selection);
+    for commit in &merge_bases {
+        let branches = base_of
+            .get(&commit.id)
+            .map(|forks| format!(" (base of {})", forks.join(", ")))
+            .unwrap_or_default();
+        writeln!(out, "merge base {}: {}{branches}", commit.short_id, commit.title).written();
+    }
+}
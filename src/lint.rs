@@ -0,0 +1,72 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::conventional::{self, commit_type};
+use crate::git_runner::GitRunner;
+use crate::selection::Selection;
+use std::collections::HashMap;
+
+/// Default for `tree.maxSubjectLength`.
+const DEFAULT_SUBJECT_LENGTH: usize = 72;
+
+/// Implements --lint: checks each outstanding commit (other than merges)
+/// against the message rules, returning the problems found, keyed by commit
+/// ID. The rules are:
+/// - The subject is at most `tree.maxSubjectLength` characters long.
+/// - If `tree.conventional` is set, the subject is a Conventional Commit.
+/// - The message has each trailer in `tree.requiredTrailer` (e.g.
+///   `Signed-off-by`).
+pub fn lint(git: &GitRunner, selection: &Selection) -> HashMap<String, Vec<String>> {
+    let max_length =
+        git.settings().get("tree.maxSubjectLength").map_or(DEFAULT_SUBJECT_LENGTH, |length| {
+            length.parse().expect("invalid tree.maxSubjectLength")
+        });
+    let conventional = conventional::enabled(git);
+    let required = git.settings().get_all("tree.requiredTrailer");
+    let output = git
+        .internal("log")
+        .args([
+            "--no-merges",
+            "--format=%H%x00%s%x00%(trailers:only,unfold,keyonly,separator=%x01)",
+        ])
+        .args(selection.outstanding_revisions())
+        .arg("--")
+        .output()
+        .expect("failed to run git");
+    assert!(output.status.success(), "git returned unsuccessful status {}", output.status);
+    let output = String::from_utf8_lossy(&output.stdout);
+    let mut problems = HashMap::new();
+    for line in output.lines() {
+        let mut fields = line.splitn(3, '\0');
+        let mut field = || fields.next().expect("truncated git log output");
+        let (id, subject, trailers) = (field(), field(), field());
+        let mut found = vec![];
+        let length = subject.chars().count();
+        if length > max_length {
+            found.push(format!("subject is {length} characters (limit {max_length})"));
+        }
+        if conventional && commit_type(subject).is_none() {
+            found.push("subject is not a Conventional Commit".into());
+        }
+        for trailer in &required {
+            if !trailers.split('\u{1}').any(|key| key.eq_ignore_ascii_case(trailer)) {
+                found.push(format!("missing {trailer} trailer"));
+            }
+        }
+        if !found.is_empty() {
+            problems.insert(id.into(), found);
+        }
+    }
+    problems
+}
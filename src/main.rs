@@ -17,40 +17,326 @@
 //! should be displayed.
 
 // The "interesting branches" are all local branches and all remote branches
-// that are tracked by a local branch. The "interesting commits" are the commits
-// pointed to by the interesting branches plus the HEAD commit. This tool
-// displays the interesting commits, their collective merge bases, and any
-// commits on the paths between the merge bases and the interesting commits.
+// that are tracked by (or pushed to from) a local branch. The "interesting
+// commits" are the commits pointed to by the interesting branches plus the HEAD
+// commit (and the detached HEADs of other worktrees). This tool displays the
+// interesting commits, their collective merge bases, and any commits on the
+// paths between the merge bases and the interesting commits.
 
+extern crate alloc;
+
+mod ages;
+mod alias;
+mod annotations;
+mod args;
+mod bisect_hints;
+mod blame_base;
+mod branch_stats;
+mod bundle;
+mod cache;
+mod changelog;
+mod codeowners;
+mod commits;
+mod compare;
+mod config_file;
+mod conventional;
+mod decorations;
+mod dependencies;
+mod diff_branches;
+mod dirty;
+mod export;
+mod finder;
+mod forge;
+mod ghosts;
+mod git_errors;
+mod git_runner;
+mod heuristics;
 mod includes_excludes;
 mod interesting_branches;
+mod is_ancestor;
+mod json;
+mod large_commits;
+mod layout;
+mod linear;
+mod lint;
+mod markdown;
 mod merge_bases;
+mod ndjson;
+mod output;
+mod pick;
+mod protection;
+mod pull_requests;
+mod reflog;
+mod render;
+mod repos;
+mod revision_args;
+mod search;
+mod select;
+mod selection;
+mod settings;
+mod signals;
+mod stats;
+mod status;
+mod svg;
+mod switch;
+mod tags;
+mod timeline;
+mod trunk;
+mod undo_info;
+mod watched;
+mod worktrees;
 
-use includes_excludes::includes_excludes;
-use interesting_branches::interesting_branches;
-use merge_bases::merge_bases;
-use std::env::args_os;
-use std::process::Command;
+use ages::ages;
+use annotations::Annotations;
+use args::{help, Args, Mode};
+use bisect_hints::bisect_hints;
+use blame_base::blame_base;
+use bundle::bundle;
+use changelog::changelog;
+use commits::Merges;
+use compare::compare;
+use core::iter::once;
+use decorations::hide_patterns;
+use diff_branches::diff_branches;
+use export::export;
+use git_runner::GitRunner;
+use interesting_branches::BranchFilter;
+use is_ancestor::is_ancestor;
+use linear::linear;
+use markdown::markdown;
+use ndjson::ndjson;
+use output::Written as _;
+use pick::pick;
+use render::render;
+use repos::{summary, Repo};
+use revision_args::revision_args;
+use search::search;
+use select::select;
+use selection::Selection;
+use stats::stats;
+use status::status;
+use std::env::{args_os, var_os};
+use std::io::{stdin, stdout, Write as _};
+use svg::svg;
+use switch::switch;
+use timeline::timeline;
+use undo_info::undo_info;
 
 fn main() {
+    let raw_args: Vec<_> = args_os().skip(1).collect();
+    // The cache is only valid for the same command line.
+    let cache_key = format!("{raw_args:?}");
+    // The aliases and default arguments come from the repository the command
+    // line names, whose runner is reused if the expanded arguments agree.
+    let selected = alias::runner(&raw_args);
+    let settings = selected.settings();
+    let args = Args::parse(alias::with_defaults(settings, alias::expand(settings, raw_args)));
+    if args.help {
+        return write!(stdout().lock(), "{}", help()).written();
+    }
+    check_args(&args);
     // Capacity estimate is a guess -- 4x as large as a SHA-256 hash seems
     // reasonable (and is a power of two).
     let mut buffer = Vec::with_capacity(256);
-    let interesting_branches = interesting_branches(&mut buffer);
-    let merge_bases = merge_bases(&mut buffer, &interesting_branches);
-    let (includes, excludes) = includes_excludes(buffer, interesting_branches, &merge_bases);
-    Command::new("git")
-        .arg("log")
-        .args(args_os().skip(1))
-        .args(includes)
-        .arg("--not")
-        .args(merge_bases.into_iter().map(|mut id| {
-            id.push_str("^@");
-            id
-        }))
-        .args(excludes)
-        .spawn()
-        .expect("Failed to run git")
-        .wait()
-        .expect("failed to wait for git");
+    let refs = args.refs_from_stdin.then(|| {
+        stdin()
+            .lines()
+            .map(|line| line.expect("stdin read failed").trim().to_owned())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+    });
+    let filter = branch_filter(&args);
+    if args.repos.is_empty() {
+        let git = selected.reuse(
+            args.no_replace_objects,
+            None,
+            args.git_dir.clone(),
+            args.work_tree.clone(),
+        );
+        let compute =
+            |scratch: &mut Vec<u8>, chosen| Selection::compute(&git, scratch, chosen, &filter);
+        if var_os(cache::REFRESH).is_some() {
+            return cache::refresh(&git, &cache_key, || compute(&mut buffer, refs));
+        }
+        // Refs from stdin are not part of the cache key, and the modes that act
+        // on the repository must see its current state.
+        let selection = if refs.is_some() || args.needs_fresh_selection() {
+            compute(&mut buffer, refs)
+        } else {
+            cache::selection(&git, &cache_key, || compute(&mut buffer, None))
+        };
+        return run(&git, &mut buffer, selection, args);
+    }
+    let mut selected = Some(selected);
+    let repos: Vec<_> = args
+        .repos
+        .iter()
+        .map(|path| {
+            // Only the first --repo's runner can have been read already.
+            let (no_replace_objects, repo) = (args.no_replace_objects, Some(path.clone()));
+            let (git_dir, work_tree) = (args.git_dir.clone(), args.work_tree.clone());
+            let git = match selected.take() {
+                Some(first) => first.reuse(no_replace_objects, repo, git_dir, work_tree),
+                None => GitRunner::new(no_replace_objects, repo, git_dir, work_tree),
+            };
+            let selection = Selection::compute(&git, &mut buffer, refs.clone(), &filter);
+            Repo { path: path.clone(), git, selection }
+        })
+        .collect();
+    summary(&repos, args.all_remotes);
+    for Repo { path, git, selection } in repos {
+        writeln!(stdout().lock(), "\n==> {path} <==").written();
+        run(&git, &mut buffer, selection, args.clone());
+    }
+}
+
+/// Checks that the options in `args` can be used together.
+fn check_args(args: &Args) {
+    assert!(
+        args.mark_contained_in.is_none()
+            || matches!(args.mode, Some(Mode::Changelog(_) | Mode::Markdown | Mode::Svg)),
+        "--mark-contained-in requires --changelog, --format=markdown, or --format=svg"
+    );
+    assert!(
+        args.paths.is_empty() || args.mode.is_some(),
+        "--path requires a native output format or subcommand; pass paths to git log after --"
+    );
+    assert!(
+        args.labels.is_empty() || matches!(args.mode, Some(Mode::Markdown | Mode::Svg)),
+        "--label requires --format=markdown or --format=svg"
+    );
+    assert!(
+        !args.lint || matches!(args.mode, Some(Mode::Markdown | Mode::Svg)),
+        "--lint requires --format=markdown or --format=svg"
+    );
+    assert!(
+        !args.reviewers || matches!(args.mode, Some(Mode::Markdown)),
+        "--reviewers requires --format=markdown"
+    );
+    assert!(!args.ghosts || matches!(args.mode, Some(Mode::Svg)), "--ghosts requires --format=svg");
+    assert!(
+        !args.refs_from_stdin || !matches!(args.mode, Some(Mode::IsAncestor)),
+        "is-ancestor reads standard input, so it cannot be used with --refs-from-stdin"
+    );
+    assert!(!args.staged || matches!(args.mode, Some(Mode::Svg)), "--staged requires --format=svg");
+}
+
+/// Returns the branch filter the command line asks for.
+fn branch_filter(args: &Args) -> BranchFilter {
+    BranchFilter {
+        include: args.include_refs.clone(),
+        revisions: args.positives.clone(),
+        stash: args.stash,
+        review: args.review_refs,
+        remotes: !args.no_remotes,
+        only_remotes: args.remotes.clone(),
+        all_remotes: args.all_remotes,
+        exclude: args.exclude_branches.clone(),
+        only: args.only_branches.clone(),
+        recent: args.recent,
+        max_age: args.branch_since,
+        authors: args.branch_authors.clone(),
+        mine: args.mine,
+        max_branches: args.max_branches,
+        hide_merged: args.hide_merged.clone(),
+        remote_view: args.remote_view.clone(),
+        profile: args.profile,
+        head: args.head.clone(),
+        pull_requests: args.pull_requests,
+        bases: args.bases.clone(),
+        pairwise: args.pairwise,
+    }
+}
+
+/// Shows `selection` as requested by `args`.
+/// Precondition: `buffer` must be empty.
+fn run(git: &GitRunner, buffer: &mut Vec<u8>, selection: Selection, args: Args) {
+    let selects_refs = args.selects_refs();
+    let negatives = args.negatives;
+    let merges = args.merges.unwrap_or_else(|| {
+        let hidden = git.settings().get_bool("tree.hideMerges") == Some(true);
+        if hidden {
+            Merges::Hidden
+        } else {
+            Merges::Shown
+        }
+    });
+    let selection = Selection { paths: args.paths, merges, ..selection };
+    let annotations = || {
+        let contained_in = args.mark_contained_in;
+        let (lint, reviewers, no_stats) = (args.lint, args.reviewers, args.no_stats);
+        Annotations::load(git, &selection, contained_in, &args.labels, lint, reviewers, no_stats)
+    };
+    match args.mode {
+        None => {}
+        Some(Mode::Ages) => return ages(git, &selection),
+        Some(Mode::Args) => return revision_args(&selection),
+        Some(Mode::BisectHints(bad)) => return bisect_hints(git, &selection, bad.as_deref()),
+        Some(Mode::BlameBase(branch)) => return blame_base(git, &selection, &branch),
+        Some(Mode::Bundle(file)) => return bundle(git, &selection, &file),
+        Some(Mode::Changelog(branch)) => {
+            let annotations = annotations();
+            return changelog(git, buffer, &selection, &annotations, branch.as_deref());
+        }
+        Some(Mode::Compare(base)) => return compare(git, &selection, &base),
+        Some(Mode::DiffBranches(branches)) => {
+            return diff_branches(git, buffer, &selection, &branches);
+        }
+        Some(Mode::Export) => return export(git, buffer, &selection),
+        Some(Mode::Graph) => return render(git, buffer, &selection),
+        Some(Mode::IsAncestor) => return is_ancestor(git, buffer, &selection),
+        Some(Mode::Linear) => return linear(git, buffer, &selection, args.no_stats),
+        Some(Mode::Markdown) => return markdown(git, buffer, &selection, &annotations()),
+        Some(Mode::Ndjson) => return ndjson(git, buffer, &selection),
+        Some(Mode::Pick(choice)) => return pick(git, &selection, choice),
+        Some(Mode::Search(regex)) => return search(git, buffer, &selection, &regex),
+        Some(Mode::Select) => return select(&selection),
+        Some(Mode::Stats) => return stats(git, buffer, &selection),
+        Some(Mode::Status) => return status(git, &selection),
+        Some(Mode::Svg) => {
+            return svg(git, buffer, &selection, &annotations(), args.staged, args.ghosts);
+        }
+        Some(Mode::Switch) => return switch(git, &selection),
+        Some(Mode::Timeline) => return timeline(git, buffer, &selection),
+        Some(Mode::UndoInfo(branch)) => return undo_info(git, &branch),
+    }
+    dirty::write_header(git);
+    let mut log = git.command("log");
+    // This precedes the passthrough arguments so that any format given on the
+    // command line takes precedence.
+    if let Some(pretty) = git.settings().get("tree.pretty") {
+        log.arg(format!("--pretty={pretty}"));
+    }
+    log.args(merges.log_option());
+    log.args(
+        hide_patterns(git).into_iter().map(|pattern| format!("--decorate-refs-exclude={pattern}")),
+    );
+    // With --repo, each repository's log is one section of the output, which
+    // should not be split across pagers.
+    if !args.repos.is_empty() {
+        log.env("GIT_PAGER", "cat");
+    }
+    // Revisions must precede the paths given after --, which replace
+    // tree.scope.
+    let mut options = args.passthrough;
+    let paths = match options.iter().position(|arg| arg == "--") {
+        Some(idx) => options.split_off(idx),
+        None => once("--".into()).chain(selection.scope.iter().map(Into::into)).collect(),
+    };
+    // The user's revisions precede git-tree's so that its --not does not
+    // negate them.
+    log.args(options);
+    // If the user chose the refs (e.g. with --all), git-tree only cuts off the
+    // history below the merge bases, rather than adding its own refs.
+    if selects_refs {
+        log.args(selection.history_limit());
+    } else {
+        log.args(selection.into_revisions());
+    }
+    // Both end with git-tree's --not in effect.
+    log.args(negatives);
+    if paths.len() > 1 {
+        log.args(paths);
+    }
+    git_errors::run(git, log);
 }
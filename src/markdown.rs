@@ -0,0 +1,135 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::annotations::Annotations;
+use crate::commits::{merge_base_commits, outstanding_commits, Commit};
+use crate::forge::Forge;
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::protection::Protection;
+use crate::selection::Selection;
+use std::io::{stdout, StdoutLock, Write as _};
+
+/// Implements `--format=markdown`, which prints each interesting branch with
+/// its commits (since the merge base) as a nested Markdown list, followed by
+/// the merge bases with the branches that fork from them. Release tags are
+/// shown as milestones above the commits they point to. Each branch is followed
+/// by how far it is from its upstream, the branches it depends on, and its
+/// reviewers (with --reviewers).
+/// Precondition: `buffer` must be empty.
+pub fn markdown(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    selection: &Selection,
+    annotations: &Annotations,
+) {
+    let commits = outstanding_commits(git, buffer, selection);
+    let merge_bases = merge_base_commits(git, buffer, selection);
+    let forge = Forge::detect(git);
+    let protection = Protection::load(git);
+    let mut out = stdout().lock();
+    for (idx, name) in selection.interesting_branches.iter().enumerate() {
+        let protected = if protection.covers(name) { " (protected)" } else { "" };
+        write!(out, "- `{name}`{protected}").written();
+        if let Some(stats) = annotations.stats.get(name) {
+            write!(out, " *({})*", escape(stats)).written();
+        }
+        if let Some(dependencies) = annotations.dependencies.get(name) {
+            let names: Vec<_> =
+                dependencies.iter().map(|dependency| format!("`{dependency}`")).collect();
+            write!(out, " *(depends on {})*", names.join(", ")).written();
+        }
+        if let Some(reviewers) = annotations.reviewers.get(name) {
+            write!(out, " *(reviewers: {})*", escape(&reviewers.join(", "))).written();
+        }
+        writeln!(out).written();
+        let mut empty = true;
+        for commit in commits.iter().filter(|commit| commit.branches.binary_search(&idx).is_ok()) {
+            write_milestones(&mut out, "  ", annotations, commit);
+            write!(out, "  - ").written();
+            write_commit(&mut out, forge.as_ref(), annotations, commit);
+            empty = false;
+        }
+        if empty {
+            writeln!(out, "  - *no commits since the merge base*").written();
+        }
+    }
+    for commit in &merge_bases {
+        write_milestones(&mut out, "", annotations, commit);
+        write!(out, "- Merge base: ").written();
+        write_commit(&mut out, forge.as_ref(), annotations, commit);
+        if let Some(branches) = annotations.base_of.get(&commit.id) {
+            let names: Vec<_> = branches.iter().map(|name| format!("`{name}`")).collect();
+            writeln!(out, "  - *base of {}*", names.join(", ")).written();
+        }
+    }
+}
+
+/// Writes a list item marking each release tag that points at `commit`.
+fn write_milestones(
+    out: &mut StdoutLock,
+    indent: &str,
+    annotations: &Annotations,
+    commit: &Commit,
+) {
+    for tag in annotations.release_tags.get(&commit.id).into_iter().flatten() {
+        writeln!(out, "{indent}- **Release `{tag}`**").written();
+    }
+}
+
+/// Writes `commit`'s abbreviated ID (linked to the forge, if known), title,
+/// user-defined labels, problems, and containment annotation, followed by
+/// a newline.
+fn write_commit(
+    out: &mut StdoutLock,
+    forge: Option<&Forge>,
+    annotations: &Annotations,
+    commit: &Commit,
+) {
+    match forge {
+        None => write!(out, "`{}`", commit.short_id),
+        Some(forge) => write!(out, "[`{}`]({})", commit.short_id, forge.commit_url(&commit.id)),
+    }
+    .written();
+    write!(out, " {}", escape(&commit.title)).written();
+    for label in annotations.labels.get(&commit.id).into_iter().flatten() {
+        write!(out, " **[{}]**", escape(label)).written();
+    }
+    if let Some(problems) = annotations.problems.get(&commit.id) {
+        write!(out, " \u{26a0} *{}*", escape(&problems.join("; "))).written();
+    }
+    write_containment(out, annotations, commit);
+    writeln!(out).written();
+}
+
+/// If --mark-contained-in was passed, writes whether `commit` is reachable from
+/// the given ref.
+pub fn write_containment(out: &mut StdoutLock, annotations: &Annotations, commit: &Commit) {
+    if let Some(containment) = annotations.containment.as_ref() {
+        let not = if containment.contains(&commit.id) { "" } else { "**not** " };
+        write!(out, " *({not}in `{}`)*", containment.refname()).written();
+    }
+}
+
+/// Escapes characters that Markdown would otherwise interpret.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -13,39 +13,71 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::git_runner::GitRunner;
+use crate::trunk::default_branch;
 use core::str;
-use std::io::{BufRead as _, BufReader};
-use std::process::{Command, Stdio};
+use std::io::Read as _;
 
-/// Returns all merge bases of the interesting commits.
-/// Precondition: `buffer` must be empty
-/// Postcondition: `buffer` will be empty
-pub fn merge_bases(buffer: &mut Vec<u8>, interesting_branches: &Vec<String>) -> Vec<String> {
-    let mut git = Command::new("git")
-        .args(["merge-base", "-a", "--octopus", "HEAD"])
-        .args(interesting_branches)
-        .stdout(Stdio::piped())
-        .spawn()
-        .expect("failed to run git");
-    let mut merge_bases = Vec::with_capacity(1);
-    let mut reader = BufReader::new(git.stdout.as_mut().unwrap());
-    while let Some(len) =
-        reader.read_until(b'\n', buffer).expect("git stdout read failed").checked_sub(1)
-    {
-        // Reserve enough space for the merge base plus a trailing ^@ (used in
-        // the final `git log` invocation).
-        #[allow(
-            clippy::arithmetic_side_effects,
-            reason = "len is < the size of an allocation so adding 2 shouldn't overflow usize"
-        )]
-        let mut merge_base = String::with_capacity(len + 2);
-        merge_base
-            .push_str(str::from_utf8(buffer.get(..len).unwrap()).expect("non-utf-8 git output"));
-        merge_bases.push(merge_base);
-        buffer.clear();
-    }
-    drop(reader);
-    let status = git.wait().expect("failed to wait for git");
-    assert!(status.success(), "git returned unsuccessful status {status}");
+/// Returns all merge bases of the interesting commits, which include `head`
+/// (usually HEAD) if it is given.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn merge_bases(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    interesting_branches: &Vec<String>,
+    head: Option<&str>,
+) -> Vec<String> {
+    let mut child = git.spawn_watched(
+        git.internal("merge-base").args(["-a", "--octopus"]).args(head).args(interesting_branches),
+    );
+    child.stdout().read_to_end(buffer).expect("git stdout read failed");
+    child.finish();
+    let merge_bases = str::from_utf8(buffer)
+        .expect("non-utf-8 git output")
+        .lines()
+        .map(|line| {
+            // Reserve enough space for the merge base plus a trailing ^@ (used
+            // in the final `git log` invocation).
+            let mut merge_base = String::with_capacity(line.len().saturating_add(2));
+            merge_base.push_str(line);
+            merge_base
+        })
+        .collect();
+    buffer.clear();
     merge_bases
 }
+
+/// Implements --merge-base-mode=pairwise: returns the merge base of each
+/// interesting branch with `head` (or, without it, the default branch),
+/// without duplicates, so that one old branch does not drag every branch's base
+/// down with it. Branches at or ahead of `head` contribute no base. Falls back
+/// to `merge_bases` if there is nothing to pair the branches with, or no branch
+/// contributes a base.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn pairwise_merge_bases(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    interesting_branches: &Vec<String>,
+    head: Option<&str>,
+) -> Vec<String> {
+    let anchor = head.map(Into::into).or_else(|| default_branch(git));
+    let anchor = anchor.and_then(|name| git.query("rev-parse", &["--verify", "--quiet", &name]));
+    let Some(anchor) = anchor else {
+        return merge_bases(git, buffer, interesting_branches, head);
+    };
+    let mut pairwise: Vec<String> = vec![];
+    for branch in interesting_branches {
+        let Some(base) = git.query("merge-base", &[&anchor, branch]) else { continue };
+        // A branch at (or ahead of) the anchor would cut off the anchor's own
+        // history, so it is left to the other branches' bases.
+        if base != anchor && !pairwise.contains(&base) {
+            pairwise.push(base);
+        }
+    }
+    if pairwise.is_empty() {
+        return merge_bases(git, buffer, interesting_branches, head);
+    }
+    pairwise
+}
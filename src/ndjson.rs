@@ -0,0 +1,98 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commits::{branch_tips, stream_displayed_commits, unique_commits};
+use crate::dependencies::dependencies;
+use crate::git_runner::GitRunner;
+use crate::json::{string, strings};
+use crate::output::Written as _;
+use crate::protection::Protection;
+use crate::selection::Selection;
+use core::iter::once;
+use std::collections::HashMap;
+use std::io::{stdout, Write as _};
+
+/// Version of the objects `--format=ndjson` prints. Bumped whenever a field is
+/// removed or changes meaning; adding fields does not change it.
+const SCHEMA_VERSION: u32 = 1;
+
+/// Implements `--format=ndjson`, which prints one JSON object per line: each
+/// displayed commit (`"type":"commit"`), children before parents, then each
+/// interesting branch (`"type":"branch"`, with the commits unique to it, the
+/// bases they sit on, and the branches it depends on), then each merge base
+/// (`"type":"merge_base"`, with the branches that fork from it). Commits are
+/// printed as git lists them, before anything else is computed, so consumers
+/// can start before the walk finishes.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn ndjson(git: &GitRunner, buffer: &mut Vec<u8>, selection: &Selection) {
+    let mut out = stdout().lock();
+    stream_displayed_commits(git, buffer, selection, |commit| {
+        let branches =
+            commit.branches.iter().map(|&idx| selection.interesting_branches.get(idx).unwrap());
+        writeln!(
+            out,
+            r#"{{"schema_version":{SCHEMA_VERSION},"type":"commit","id":"{}","parents":{},"author":{},"timestamp":{},"subject":{},"branches":{}}}"#,
+            commit.id,
+            strings(&commit.parents),
+            string(&commit.author),
+            commit.timestamp,
+            string(&commit.subject),
+            strings(branches),
+        )
+        .written();
+    });
+    let tips = branch_tips(git, buffer, &selection.interesting_branches);
+    let protection = Protection::load(git);
+    let dependencies = dependencies(git, selection);
+    let mut base_of: HashMap<String, Vec<&str>> = HashMap::new();
+    for (idx, name) in selection.interesting_branches.iter().enumerate() {
+        let Some(id) = tips.iter().find(|tip| tip.1.contains(&idx)).map(|tip| tip.0) else {
+            continue;
+        };
+        let (commits, bases) = unique_commits(git, id, &selection.merge_bases);
+        for base in bases.iter().filter(|&base| selection.merge_bases.contains(base)) {
+            base_of.entry(base.clone()).or_default().push(name);
+        }
+        // A single base gives the familiar base..tip; otherwise each base is
+        // excluded separately.
+        let range = match (bases.first(), bases.len()) {
+            (Some(base), 1) => format!("{base}..{id}"),
+            _ => once(id.clone())
+                .chain(bases.iter().map(|base| format!("^{base}")))
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        writeln!(
+            out,
+            r#"{{"schema_version":{SCHEMA_VERSION},"type":"branch","name":{},"id":"{id}","bases":{},"range":{},"commits":{},"protected":{},"depends_on":{}}}"#,
+            string(name),
+            strings(&bases),
+            string(&range),
+            strings(&commits),
+            protection.covers(name),
+            strings(dependencies.get(name).map(Vec::as_slice).unwrap_or_default()),
+        )
+        .written();
+    }
+    for id in &selection.merge_bases {
+        let branches = base_of.get(id).map(Vec::as_slice).unwrap_or_default();
+        writeln!(
+            out,
+            r#"{{"schema_version":{SCHEMA_VERSION},"type":"merge_base","id":"{id}","base_of":{}}}"#,
+            strings(branches)
+        )
+        .written();
+    }
+}
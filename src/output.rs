@@ -0,0 +1,34 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::{self, ErrorKind};
+use std::process::exit;
+
+/// Checks the results of writes to standard output.
+pub trait Written {
+    /// Panics if the write failed, unless standard output is a pipe whose
+    /// reader has gone away (e.g. `git-tree --format=ndjson | head`), in which
+    /// case git-tree exits quietly, as there is no one left to write to.
+    fn written(self);
+}
+
+impl Written for io::Result<()> {
+    fn written(self) {
+        if self.as_ref().is_err_and(|error| error.kind() == ErrorKind::BrokenPipe) {
+            #[allow(clippy::exit, reason = "the rest of the output cannot be shown")]
+            exit(0);
+        }
+        self.expect("stdout write failed");
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use std::ffi::OsString;
+use std::io::{stdout, Write as _};
+use std::process::{Command, Stdio};
+
+/// Implements `git-tree pick [<n> [<command>...]]`. Without arguments, lists
+/// the interesting branches with their numbers, in columns if `column.branch`
+/// or `column.ui` asks for them (as `git branch` does). With a number, runs
+/// `command` with that branch appended to its arguments, or prints the branch
+/// if no command was given.
+pub fn pick(git: &GitRunner, selection: &Selection, choice: Option<(usize, Vec<OsString>)>) {
+    let branches = selection.sorted_branches();
+    let Some((number, command)) = choice else {
+        let mut column = git
+            .command("column")
+            .arg("--command=branch")
+            .stdin(Stdio::piped())
+            .spawn()
+            .expect("failed to run git");
+        let mut input = column.stdin.take().unwrap();
+        let width = branches.len().to_string().len();
+        let first: usize = 1;
+        for (number, branch) in (first..).zip(&branches) {
+            writeln!(input, "[{number:>width$}] {branch}").expect("git column write failed");
+        }
+        drop(input);
+        let status = column.wait().expect("failed to wait for git");
+        assert!(status.success(), "git returned unsuccessful status {status}");
+        return;
+    };
+    let branch = number.checked_sub(1).and_then(|idx| branches.get(idx));
+    assert!(branch.is_some(), "no branch is numbered {number}; run `git-tree pick` to list them");
+    let branch = branch.unwrap();
+    let Some((program, args)) = command.split_first() else {
+        return writeln!(stdout().lock(), "{branch}").written();
+    };
+    let status = Command::new(program)
+        .args(args)
+        .arg(branch)
+        .status()
+        .expect("failed to run the picked command");
+    assert!(status.success(), "the picked command returned unsuccessful status {status}");
+}
@@ -0,0 +1,58 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::decorations::glob_matches;
+use crate::git_runner::GitRunner;
+
+/// The branches that `tree.protected` protects, e.g. `main, release/*`.
+pub struct Protection {
+    patterns: Vec<String>,
+
+    /// Names of the remotes, so that `origin/main` is protected by `main`.
+    remotes: Vec<String>,
+}
+
+impl Protection {
+    /// Reads `tree.protected`, which may be repeated and whose values are
+    /// comma-separated patterns.
+    pub fn load(git: &GitRunner) -> Self {
+        let patterns: Vec<String> = git
+            .settings()
+            .get_all("tree.protected")
+            .into_iter()
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(Into::into)
+            .collect();
+        let remotes = if patterns.is_empty() {
+            vec![]
+        } else {
+            git.query("remote", &[]).unwrap_or_default().lines().map(Into::into).collect()
+        };
+        Self { patterns, remotes }
+    }
+
+    /// Returns whether `branch` is protected. A remote branch is protected if
+    /// its own name (e.g. `origin/main`) or its name on the remote (`main`)
+    /// matches a pattern.
+    pub fn covers(&self, branch: &str) -> bool {
+        let on_remote =
+            self.remotes.iter().find_map(|remote| branch.strip_prefix(&format!("{remote}/")));
+        self.patterns.iter().any(|pattern| {
+            glob_matches(pattern.as_bytes(), branch.as_bytes())
+                || on_remote.is_some_and(|name| glob_matches(pattern.as_bytes(), name.as_bytes()))
+        })
+    }
+}
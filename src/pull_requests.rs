@@ -0,0 +1,72 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use std::collections::HashMap;
+
+/// Fetches the pull requests opened from the interesting branches, returning
+/// the remote-tracking refs they were stored in (`<remote>/pull/<n>`). A pull
+/// request is matched to a branch when the remote's `refs/pull/<n>/head` and
+/// `refs/heads/<branch>` point at the same commit, so remotes that do not
+/// expose pull request refs (or cannot be reached) contribute nothing.
+pub fn pull_requests(git: &GitRunner, interesting_branches: &[String]) -> Vec<String> {
+    let remotes = git.query("remote", &[]).unwrap_or_default();
+    let mut fetched = vec![];
+    for remote in remotes.lines() {
+        let prefix = format!("{remote}/");
+        let names: Vec<_> = interesting_branches
+            .iter()
+            .filter_map(|branch| branch.strip_prefix(&prefix))
+            .map(|name| format!("refs/heads/{name}"))
+            .collect();
+        if names.is_empty() {
+            continue;
+        }
+        let Some(listing) = git.query("ls-remote", &[remote, "refs/heads/*", "refs/pull/*/head"])
+        else {
+            continue;
+        };
+        // Map from commit ID to the pull request numbers whose head it is.
+        let mut heads: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut branch_ids = vec![];
+        for (id, refname) in listing.lines().filter_map(|line| line.split_once('\t')) {
+            if let Some(number) =
+                refname.strip_prefix("refs/pull/").and_then(|rest| rest.strip_suffix("/head"))
+            {
+                heads.entry(id).or_default().push(number);
+            } else if names.iter().any(|name| name == refname) {
+                branch_ids.push(id);
+            }
+        }
+        let mut numbers: Vec<_> =
+            branch_ids.iter().filter_map(|id| heads.get(id)).flatten().copied().collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        if numbers.is_empty() {
+            continue;
+        }
+        let refspecs = numbers
+            .iter()
+            .map(|number| format!("+refs/pull/{number}/head:refs/remotes/{remote}/pull/{number}"));
+        let status = git
+            .internal("fetch")
+            .args(["--quiet", "--no-tags", "--no-write-fetch-head", remote])
+            .args(refspecs)
+            .status()
+            .expect("failed to run git");
+        assert!(status.success(), "git fetch of {remote}'s pull requests failed: {status}");
+        fetched.extend(numbers.iter().map(|number| format!("{remote}/pull/{number}")));
+    }
+    fetched
+}
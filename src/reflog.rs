@@ -0,0 +1,53 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+
+/// An entry in a branch's reflog: a position the branch's tip moved to.
+pub struct ReflogEntry {
+    pub id: String,
+    pub short_id: String,
+
+    /// The entry's name, e.g. `feature@{2}`.
+    pub selector: String,
+
+    /// What moved the tip, e.g. `rebase (finish): ...` or `commit: ...`.
+    pub subject: String,
+}
+
+impl ReflogEntry {
+    /// Returns whether this entry rewrote the branch (rather than adding to it).
+    pub fn is_rewrite(&self) -> bool {
+        self.subject.starts_with("rebase") || self.subject.starts_with("commit (amend)")
+    }
+}
+
+/// Returns local branch `branch`'s reflog, newest first. Returns an empty list
+/// if the branch has no reflog.
+pub fn reflog(git: &GitRunner, branch: &str) -> Vec<ReflogEntry> {
+    let output = git.query(
+        "reflog",
+        &["show", "--format=%H%x00%h%x00%gd%x00%gs", &format!("refs/heads/{branch}"), "--"],
+    );
+    output
+        .as_deref()
+        .unwrap_or_default()
+        .lines()
+        .map(|line| {
+            let mut fields = line.splitn(4, '\0').map(String::from);
+            let mut field = || fields.next().expect("truncated git reflog output");
+            ReflogEntry { id: field(), short_id: field(), selector: field(), subject: field() }
+        })
+        .collect()
+}
@@ -0,0 +1,208 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Draws the commit graph as text itself, rather than through
+//! `git log --graph`, using the same lanes as `--format=svg`.
+//!
+//! Each commit gets one line: the lanes, with `*` in the commit's lane and `|`
+//! in the lanes passing by it, followed by the commit's short ID, the branches
+//! pointing at it, and its title. Where an edge changes lanes, a connector line
+//! with `/` or `\` goes between the commits' lines.
+
+use crate::commits::{branch_tips, displayed_commits, Commit};
+use crate::git_runner::GitRunner;
+use crate::layout::{lane_order, layout, Layout};
+use crate::output::Written as _;
+use crate::selection::Selection;
+use core::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::{stdout, IsTerminal as _, Write as _};
+
+/// Lane colors, as ANSI SGR codes (blue, red, green, magenta, yellow, cyan).
+/// Lanes beyond the end of the palette reuse its colors.
+const PALETTE: [u8; 6] = [34, 31, 32, 35, 33, 36];
+
+/// A character of the graph and the lane whose color it is drawn in.
+type Cell = (char, usize);
+
+/// Implements `--format=graph`. Colors are used if standard output is a
+/// terminal.
+/// Precondition: `buffer` must be empty.
+pub fn render(git: &GitRunner, buffer: &mut Vec<u8>, selection: &Selection) {
+    let mut commits = displayed_commits(git, buffer, selection);
+    let folded = git.settings().get("tree.foldUpstream").map_or_else(HashMap::new, |limit| {
+        let limit = limit.parse().expect("invalid tree.foldUpstream");
+        fold_upstreams(git, selection, &mut commits, limit)
+    });
+    let tips = branch_tips(git, buffer, &selection.interesting_branches);
+    let layout = layout(&commits, &lane_order(git, selection, &tips));
+    let head = git.query("rev-parse", &["--verify", "--quiet", "HEAD"]);
+    let color = stdout().is_terminal();
+    let mut out = stdout().lock();
+    for (row, commit) in commits.iter().enumerate() {
+        let column = *layout.columns.get(row).unwrap();
+        let mut labels: Vec<_> = tips
+            .get(&commit.id)
+            .into_iter()
+            .flatten()
+            .map(|&idx| {
+                let name = selection.interesting_branches.get(idx).unwrap();
+                folded.get(name).map_or(name.as_str(), String::as_str)
+            })
+            .collect();
+        labels.sort_unstable();
+        if head.as_deref() == Some(commit.id.as_str()) {
+            labels.insert(0, "HEAD");
+        }
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", paint(&format!("({})", labels.join(", ")), column, color))
+        };
+        let short_id = if color {
+            format!("\x1b[33m{}\x1b[m", commit.short_id)
+        } else {
+            commit.short_id.clone()
+        };
+        writeln!(
+            out,
+            "{} {short_id}{labels} {}",
+            draw(&commit_cells(&layout, row), color),
+            commit.title
+        )
+        .written();
+        let connector = connector_cells(&layout, row);
+        if connector.iter().any(|&(glyph, _)| glyph != ' ' && glyph != '|') {
+            writeln!(out, "{}", draw(&connector, color).trim_end()).written();
+        }
+    }
+}
+
+/// Folds each local branch that has diverged from its upstream by at most
+/// `limit` commits (ahead plus behind, from `tree.foldUpstream`) into the
+/// local branch's lane, by removing the commits only the upstream contains.
+/// Returns the labels that replace the folded local branches' names, e.g.
+/// `main ↑1 ↓2 origin/main` for a branch one commit ahead of and two
+/// commits behind its upstream.
+fn fold_upstreams(
+    git: &GitRunner,
+    selection: &Selection,
+    commits: &mut Vec<Commit>,
+    limit: usize,
+) -> HashMap<String, String> {
+    let index =
+        |name: &str| selection.interesting_branches.iter().position(|branch| branch == name);
+    let listing = git
+        .query("for-each-ref", &["--format=%(refname:short)%00%(upstream:short)", "refs/heads"])
+        .unwrap_or_default();
+    let mut labels = HashMap::new();
+    for line in listing.lines() {
+        let Some((branch, upstream)) = line.split_once('\0') else { continue };
+        let (Some(local), Some(remote)) = (index(branch), index(upstream)) else { continue };
+        let contains = |commit: &Commit, idx: usize| commit.branches.binary_search(&idx).is_ok();
+        let ahead = commits
+            .iter()
+            .filter(|commit| contains(commit, local) && !contains(commit, remote))
+            .count();
+        let behind: Vec<_> = commits
+            .iter()
+            .filter(|commit| contains(commit, remote) && !contains(commit, local))
+            .collect();
+        // Fold only diverged pairs (others already share a lane), and only if
+        // no other branch needs the upstream's commits.
+        if ahead == 0
+            || behind.is_empty()
+            || ahead.saturating_add(behind.len()) > limit
+            || behind.iter().any(|commit| commit.branches.len() > 1)
+        {
+            continue;
+        }
+        let label = format!("{branch} \u{2191}{ahead} \u{2193}{} {upstream}", behind.len());
+        labels.insert(branch.to_owned(), label);
+        commits.retain(|commit| commit.branches != [remote]);
+    }
+    labels
+}
+
+/// Returns the graph on the line of the commit in `row`.
+fn commit_cells(layout: &Layout, row: usize) -> Vec<Cell> {
+    let mut cells = vec![(' ', 0); layout.width.saturating_mul(2).saturating_sub(1)];
+    for edge in layout.edges.iter().filter(|edge| edge.child < row && row < edge.parent) {
+        *cells.get_mut(edge.lane.saturating_mul(2)).unwrap() = ('|', edge.lane);
+    }
+    let column = *layout.columns.get(row).unwrap();
+    *cells.get_mut(column.saturating_mul(2)).unwrap() = ('*', column);
+    cells
+}
+
+/// Returns the graph on the line between the commits in `row` and `row + 1`.
+fn connector_cells(layout: &Layout, row: usize) -> Vec<Cell> {
+    let mut cells = vec![(' ', 0); layout.width.saturating_mul(2).saturating_sub(1)];
+    let below = row.saturating_add(1);
+    for edge in layout.edges.iter().filter(|edge| edge.child <= row && row < edge.parent) {
+        // An edge leaves its child's column right below the child, and joins
+        // its parent's column right above the parent.
+        let from = if edge.child == row { *layout.columns.get(row).unwrap() } else { edge.lane };
+        let to = if edge.parent == below { *layout.columns.get(below).unwrap() } else { edge.lane };
+        let (slash, position, underscores) = match from.cmp(&to) {
+            Ordering::Equal => {
+                *cells.get_mut(from.saturating_mul(2)).unwrap() = ('|', edge.lane);
+                continue;
+            }
+            Ordering::Greater => {
+                let position = from.saturating_mul(2).saturating_sub(1);
+                ('/', position, to.saturating_mul(2).saturating_add(1)..position)
+            }
+            Ordering::Less => {
+                let position = from.saturating_mul(2).saturating_add(1);
+                ('\\', position, position.saturating_add(1)..to.saturating_mul(2))
+            }
+        };
+        // The slash sits next to the child's column, and underscores lead from
+        // it to the parent's column without covering other edges.
+        *cells.get_mut(position).unwrap() = (slash, edge.lane);
+        for cell in cells.get_mut(underscores).unwrap() {
+            if cell.0 == ' ' {
+                *cell = ('_', edge.lane);
+            }
+        }
+    }
+    cells
+}
+
+/// Turns `cells` into text, coloring each character by its lane if `color` is
+/// set.
+fn draw(cells: &[Cell], color: bool) -> String {
+    cells
+        .iter()
+        .map(
+            |&(glyph, lane)| {
+                if glyph == ' ' {
+                    " ".into()
+                } else {
+                    paint(&glyph.to_string(), lane, color)
+                }
+            },
+        )
+        .collect()
+}
+
+/// Returns `text` in `lane`'s color if `color` is set.
+fn paint(text: &str, lane: usize, color: bool) -> String {
+    if !color {
+        return text.into();
+    }
+    let code = PALETTE.get(lane.checked_rem(PALETTE.len()).unwrap()).unwrap();
+    format!("\x1b[{code}m{text}\x1b[m")
+}
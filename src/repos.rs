@@ -0,0 +1,150 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use core::iter::once;
+use std::collections::HashSet;
+use std::io::{stdout, Write as _};
+
+/// A repository passed to --repo, with its computed selection.
+pub struct Repo {
+    pub path: String,
+    pub git: GitRunner,
+    pub selection: Selection,
+}
+
+/// Subtotals for one remote's branches in a repository's selection.
+struct RemoteRow {
+    name: String,
+    branches: usize,
+
+    /// How many of the branches a local branch tracks and has diverged from.
+    diverged: usize,
+
+    /// The outstanding commits the branches contain.
+    outstanding: usize,
+}
+
+/// Prints a table comparing the selections of several repositories, with one
+/// row per repository. With --all-remotes (`by_remote`), each repository's row
+/// is followed by one for each remote with interesting branches, and a column
+/// counts the remote branches that a local branch has diverged from.
+pub fn summary(repos: &[Repo], by_remote: bool) {
+    let rows: Vec<_> = repos
+        .iter()
+        .map(|repo| {
+            let outstanding = outstanding(repo, repo.selection.includes.iter().map(String::as_str));
+            let remotes = if by_remote { remote_rows(repo) } else { vec![] };
+            (repo, outstanding, remotes)
+        })
+        .collect();
+    let width = rows
+        .iter()
+        .flat_map(|row| {
+            let names = row.2.iter().map(|remote| remote.name.chars().count().saturating_add(2));
+            once(row.0.path.chars().count()).chain(names)
+        })
+        .chain([4])
+        .max()
+        .unwrap();
+    let mut out = stdout().lock();
+    write!(out, "{:width$}  branches  merge bases  outstanding", "repo").written();
+    writeln!(out, "{}", if by_remote { "  diverged" } else { "" }).written();
+    for (repo, count, remotes) in rows {
+        write!(
+            out,
+            "{:width$}  {:>8}  {:>11}  {count:>11}",
+            repo.path,
+            repo.selection.interesting_branches.len(),
+            repo.selection.merge_bases.len(),
+        )
+        .written();
+        if by_remote {
+            let diverged = remotes.iter().map(|row| row.diverged).sum::<usize>();
+            write!(out, "  {diverged:>8}").written();
+        }
+        writeln!(out).written();
+        let name_width = width.saturating_sub(2);
+        for RemoteRow { name, branches, diverged, outstanding } in remotes {
+            writeln!(
+                out,
+                "  {name:name_width$}  {branches:>8}  {:>11}  {outstanding:>11}  {diverged:>8}",
+                ""
+            )
+            .written();
+        }
+    }
+}
+
+/// Counts the commits in `repo`'s outstanding commits (within `tree.scope`)
+/// that `revisions` contain.
+fn outstanding<'rev>(repo: &'rev Repo, revisions: impl Iterator<Item = &'rev str>) -> usize {
+    let mut args = vec!["--count"];
+    args.extend(revisions);
+    args.push("--not");
+    args.extend(repo.selection.merge_bases.iter().map(String::as_str));
+    args.extend(repo.selection.excludes.iter().map(String::as_str));
+    args.push("--");
+    args.extend(repo.selection.scope.iter().map(String::as_str));
+    repo.git
+        .query("rev-list", &args)
+        .expect("git rev-list --count failed")
+        .parse::<usize>()
+        .expect("invalid git rev-list --count output")
+}
+
+/// Returns the subtotals for each remote with interesting branches in `repo`,
+/// in the order `git remote` lists them.
+fn remote_rows(repo: &Repo) -> Vec<RemoteRow> {
+    let remote_branches = repo
+        .git
+        .query("for-each-ref", &["--format=%(refname:lstrip=2)", "refs/remotes"])
+        .unwrap_or_default();
+    let remote_branches: HashSet<_> = remote_branches.lines().collect();
+    // Upstreams that their local branch is both ahead of and behind.
+    let tracking = repo
+        .git
+        .query("for-each-ref", &["--format=%(upstream:lstrip=2)%00%(upstream:track)", "refs/heads"])
+        .unwrap_or_default();
+    let diverged: HashSet<_> = tracking
+        .lines()
+        .filter_map(|line| line.split_once('\0'))
+        .filter(|&(_, track)| track.contains("ahead") && track.contains("behind"))
+        .map(|(upstream, _)| upstream)
+        .collect();
+    let remotes = repo.git.query("remote", &[]).unwrap_or_default();
+    remotes
+        .lines()
+        .filter_map(|remote| {
+            let branches: Vec<_> = repo
+                .selection
+                .interesting_branches
+                .iter()
+                .map(String::as_str)
+                .filter(|&branch| {
+                    remote_branches.contains(branch)
+                        && branch.strip_prefix(remote).is_some_and(|rest| rest.starts_with('/'))
+                })
+                .collect();
+            (!branches.is_empty()).then(|| RemoteRow {
+                name: remote.into(),
+                branches: branches.len(),
+                diverged: branches.iter().filter(|&branch| diverged.contains(branch)).count(),
+                outstanding: outstanding(repo, branches.iter().copied()),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,36 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::export::quote;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use std::io::{stdout, Write as _};
+
+/// Implements `git-tree args`, which prints the revision arguments that select
+/// the displayed commits on one line, for other git commands, e.g.
+/// `git rev-list $(git tree args)`. Arguments the shell would mangle are
+/// quoted, though the commit IDs and options git-tree generates never are.
+pub fn revision_args(selection: &Selection) {
+    let words: Vec<_> = selection
+        .revisions()
+        .map(|word| {
+            let plain = word.bytes().all(|b| b.is_ascii_alphanumeric() || b"-_./^@~:".contains(&b));
+            if plain {
+                word
+            } else {
+                quote(&word)
+            }
+        })
+        .collect();
+    writeln!(stdout().lock(), "{}", words.join(" ")).written();
+}
@@ -0,0 +1,44 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commits::outstanding_commits;
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use std::collections::HashSet;
+use std::io::{stdout, Write as _};
+
+/// Implements `git-tree search <regex>`, which prints the outstanding commits
+/// whose messages match `regex` (an extended regular expression, as with
+/// `git log --grep -E`), along with the branches they are on.
+/// Precondition: `buffer` must be empty.
+pub fn search(git: &GitRunner, buffer: &mut Vec<u8>, selection: &Selection, regex: &str) {
+    let mut args = vec!["--extended-regexp", "--format=%H"];
+    let grep = format!("--grep={regex}");
+    args.push(&grep);
+    args.extend(selection.outstanding_revisions());
+    args.push("--");
+    let matches: HashSet<_> =
+        git.query("log", &args).expect("git log --grep failed").lines().map(String::from).collect();
+    let commits = outstanding_commits(git, buffer, selection);
+    let mut out = stdout().lock();
+    for commit in commits.iter().filter(|commit| matches.contains(&commit.id)) {
+        let branches: Vec<_> = commit
+            .branches
+            .iter()
+            .map(|&idx| selection.interesting_branches.get(idx).unwrap().as_str())
+            .collect();
+        writeln!(out, "{} {}  [{}]", commit.short_id, commit.title, branches.join(", ")).written();
+    }
+}
@@ -0,0 +1,27 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::finder::choose;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use std::io::{stdout, Write as _};
+
+/// Implements `git-tree select`, which lets the user pick an interesting branch
+/// with `fzf` and prints only its name to stdout, for use in shell functions.
+/// Cancelling fails, so that such functions can tell nothing was chosen.
+pub fn select(selection: &Selection) {
+    let branch = choose(selection);
+    assert!(branch.is_some(), "no branch was selected");
+    writeln!(stdout().lock(), "{}", branch.unwrap()).written();
+}
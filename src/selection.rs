@@ -0,0 +1,223 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commits::Merges;
+use crate::git_runner::GitRunner;
+use crate::heuristics::{heuristic, Profile};
+use crate::includes_excludes::includes_excludes;
+use crate::interesting_branches::{
+    hide_merged, namespace_refs, recent_branches, review_refs, sort as sort_branches, trunk,
+    BranchFilter,
+};
+use crate::merge_bases::{merge_bases, pairwise_merge_bases};
+use crate::pull_requests::pull_requests as fetch_pull_requests;
+use crate::worktrees::worktree_heads;
+use core::iter::once;
+
+/// The set of commits git-tree displays: the interesting commits, their merge
+/// bases, and the commits on the paths between them.
+pub struct Selection {
+    /// In the order `git branch` lists them (see `interesting_branches::sort`).
+    pub interesting_branches: Vec<String>,
+    pub merge_bases: Vec<String>,
+
+    /// Positive revisions for `git log`.
+    pub includes: Vec<String>,
+
+    /// Negative revisions for `git log`, in addition to the merge bases'
+    /// parents.
+    pub excludes: Vec<String>,
+
+    /// Pathspecs from `tree.scope`. If not empty, only commits that touch these
+    /// paths are displayed. The other selection fields do not depend on it.
+    pub scope: Vec<String>,
+
+    /// Pathspecs from --path. If not empty, native output only shows commits
+    /// that touch these paths, plus the branch tips and merge bases.
+    pub paths: Vec<String>,
+
+    /// Whether native output shows merge commits (from --no-merges,
+    /// --merges-only, and `tree.hideMerges`).
+    pub merges: Merges,
+}
+
+impl Selection {
+    /// Runs the heuristic and computes the merge bases.
+    ///
+    /// If `refs` is given, it is used as the interesting branches rather than
+    /// the heuristic's choice, with only the refs passed on the command line
+    /// (--include-ref and the revisions passed to git log) added to it.
+    ///
+    /// Otherwise, the heuristic's choice is narrowed down by `filter` (and
+    /// pruned by --hide-merged) and always includes the default branch. The
+    /// other refs `filter` includes (stash entries, review refs and pull
+    /// requests) are added, as are the refs in `tree.refNamespaces` and the
+    /// detached HEADs of other worktrees. If HEAD is detached away from the
+    /// heuristic's branches, recently checked-out refs are added too.
+    ///
+    /// The interesting branches are sorted like `git branch` sorts them. The
+    /// merge bases are the --base refs in `filter` if there are any, and are
+    /// computed (pairwise or octopus, per `filter`) otherwise.
+    /// Precondition: `buffer` must be empty.
+    /// Postcondition: `buffer` will be empty.
+    pub fn compute(
+        git: &GitRunner,
+        buffer: &mut Vec<u8>,
+        refs: Option<Vec<String>>,
+        filter: &BranchFilter,
+    ) -> Self {
+        let from_stdin = refs.is_some();
+        let mut interesting_branches = refs.unwrap_or_else(|| {
+            let mut branches = heuristic(git, buffer, filter);
+            hide_merged(git, filter, &mut branches);
+            branches.extend(trunk(git, filter, &branches));
+            branches.extend(recent_branches(git, filter, &branches));
+            branches
+        });
+        for name in &filter.include {
+            let resolved =
+                git.query("rev-parse", &["--verify", "--quiet", &format!("{name}^{{commit}}")]);
+            assert!(resolved.is_some(), "--include-ref: {name} is not a commit");
+            if !interesting_branches.contains(name) {
+                interesting_branches.push(name.clone());
+            }
+        }
+        // Revisions given to git log join the interesting branches, so that
+        // the merge bases account for them. Other arguments (such as option
+        // values) are left to git log.
+        for name in &filter.revisions {
+            let resolves = git
+                .query("rev-parse", &["--verify", "--quiet", &format!("{name}^{{commit}}")])
+                .is_some();
+            if resolves && !interesting_branches.contains(name) {
+                interesting_branches.push(name.clone());
+            }
+        }
+        if !from_stdin {
+            add_implicit_refs(git, filter, &mut interesting_branches);
+        }
+        sort_branches(git, &mut interesting_branches);
+        // --head stands in for HEAD, and --remote-view shows the remote alone,
+        // without the local HEAD.
+        let head = match (filter.head.as_deref(), filter.remote_view.is_some()) {
+            (_, true) => None,
+            (None, false) => Some("HEAD".into()),
+            (Some(head), false) => Some(resolve(git, "--head", head)),
+        };
+        let merge_bases = if !filter.bases.is_empty() {
+            filter.bases.iter().map(|name| resolve(git, "--base", name)).collect()
+        } else if filter.pairwise {
+            pairwise_merge_bases(git, buffer, &interesting_branches, head.as_deref())
+        } else {
+            merge_bases(git, buffer, &interesting_branches, head.as_deref())
+        };
+        let (includes, excludes) =
+            includes_excludes(git, buffer, &interesting_branches, &merge_bases, head.as_deref());
+        let scope = git.settings().get_all("tree.scope").into_iter().map(Into::into).collect();
+        Self {
+            interesting_branches,
+            merge_bases,
+            includes,
+            excludes,
+            scope,
+            paths: vec![],
+            merges: Merges::Shown,
+        }
+    }
+
+    /// Returns the interesting branches sorted by name, without duplicates.
+    /// Branches are numbered by their position in this list, which only
+    /// changes when branches are added or removed.
+    pub fn sorted_branches(&self) -> Vec<&str> {
+        let mut branches: Vec<_> = self.interesting_branches.iter().map(String::as_str).collect();
+        branches.sort_unstable();
+        branches.dedup();
+        branches
+    }
+
+    /// Revision arguments that select the outstanding commits: the displayed
+    /// commits other than the merge bases.
+    pub fn outstanding_revisions(&self) -> impl Iterator<Item = &str> {
+        self.includes
+            .iter()
+            .map(String::as_str)
+            .chain(once("--not"))
+            .chain(self.merge_bases.iter().map(String::as_str))
+            .chain(self.excludes.iter().map(String::as_str))
+    }
+
+    /// Revision arguments that select the displayed commits.
+    pub fn revisions(&self) -> impl Iterator<Item = String> + '_ {
+        self.includes
+            .iter()
+            .cloned()
+            .chain(once("--not".into()))
+            .chain(self.merge_bases.iter().map(|id| format!("{id}^@")))
+            .chain(self.excludes.iter().cloned())
+    }
+
+    /// Revision arguments that only exclude the history below the merge bases,
+    /// for use with revisions chosen by the user.
+    pub fn history_limit(&self) -> impl Iterator<Item = String> + '_ {
+        once("--not".into()).chain(self.merge_bases.iter().map(|id| format!("{id}^@")))
+    }
+
+    /// Consumes the selection, returning the revision arguments that select the
+    /// displayed commits. Equivalent to `revisions`, but avoids copying.
+    pub fn into_revisions(self) -> impl Iterator<Item = String> {
+        self.includes
+            .into_iter()
+            .chain(once("--not".into()))
+            .chain(self.merge_bases.into_iter().map(|mut id| {
+                id.push_str("^@");
+                id
+            }))
+            .chain(self.excludes)
+    }
+}
+
+/// Resolves a ref passed to `option` (--base or --head) to its commit ID.
+fn resolve(git: &GitRunner, option: &str, name: &str) -> String {
+    let id = git.query("rev-parse", &["--verify", "--quiet", &format!("{name}^{{commit}}")]);
+    assert!(id.is_some(), "{option}: {name} is not a commit");
+    id.unwrap()
+}
+
+/// Adds the refs that are interesting without being named on the command line:
+/// stash entries (per --stash or --mode=full), review refs, the refs in
+/// `tree.refNamespaces`, the pull requests opened from `interesting_branches`
+/// (per --pull-requests), and the detached HEADs of other worktrees.
+fn add_implicit_refs(
+    git: &GitRunner,
+    filter: &BranchFilter,
+    interesting_branches: &mut Vec<String>,
+) {
+    // Each entry is named by its reflog selector (e.g. `stash@{1}`), as
+    // only the newest one has a ref of its own.
+    if filter.stash || filter.profile == Profile::Full {
+        let entries = git.query("stash", &["list", "--format=%gd"]).unwrap_or_default();
+        interesting_branches.extend(entries.lines().map(Into::into));
+    }
+    let review = if filter.review { review_refs(git, filter) } else { vec![] };
+    for name in review.into_iter().chain(namespace_refs(git, filter)) {
+        if !interesting_branches.contains(&name) {
+            interesting_branches.push(name);
+        }
+    }
+    if filter.pull_requests {
+        let fetched = fetch_pull_requests(git, interesting_branches);
+        interesting_branches.extend(fetched);
+    }
+    interesting_branches.extend(worktree_heads(git));
+}
@@ -0,0 +1,200 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::str;
+use std::process::Command;
+
+/// A setting's key and value, as `Settings` holds it.
+pub type Entry = (String, Option<String>);
+
+/// git-tree's settings: every `tree.*` entry in the `git-tree.toml` files and
+/// in git's configuration, read with a single `git config` call when the
+/// `GitRunner` is created.
+#[derive(Default, Hash)]
+pub struct Settings {
+    /// (key, value) pairs in increasing order of precedence, so later entries
+    /// override earlier ones. Keys have their section and variable names in
+    /// lowercase, as git prints them; the value is `None` for a key without
+    /// `=` (which git treats as boolean true).
+    entries: Vec<Entry>,
+}
+
+impl Settings {
+    /// Reads the settings. `command` is a `git config` invocation from
+    /// `GitRunner::internal`, and `user_file` and `repo_file` are the entries
+    /// from the user's and the repository's `git-tree.toml`. Each file takes
+    /// precedence over the configuration that applies as widely: the user's
+    /// file is overridden by all of git's configuration, and the repository's
+    /// file overrides git's system and global configuration but not the
+    /// repository's (nor `-c` options).
+    pub fn load(mut command: Command, user_file: Vec<Entry>, repo_file: Vec<Entry>) -> Self {
+        let output = command
+            .args(["-z", "--show-scope", "--get-regexp", r"^tree\."])
+            .output()
+            .expect("failed to run git");
+        // git exits with status 1 if there are no matching entries, and with
+        // other statuses if there is no configuration to read (e.g. outside a
+        // repository with no global configuration), neither of which is an
+        // error here.
+        let output = if output.status.success() { output.stdout } else { vec![] };
+        let (shared, local) = parse(str::from_utf8(&output).expect("non-utf-8 git config output"));
+        let entries = user_file.into_iter().chain(shared).chain(repo_file).chain(local).collect();
+        Self { entries }
+    }
+
+    /// Returns the value of `key`, as with `git config --get`. Returns `None`
+    /// if it is not set or has no value.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        let key = canonical(key);
+        self.entries.iter().rev().find(|entry| entry.0 == key).and_then(|entry| entry.1.as_deref())
+    }
+
+    /// Returns every value of a multi-valued key, in order, as with
+    /// `git config --get-all`.
+    pub fn get_all(&self, key: &str) -> Vec<&str> {
+        let key = canonical(key);
+        self.entries
+            .iter()
+            .filter(|entry| entry.0 == key)
+            .filter_map(|entry| entry.1.as_deref())
+            .collect()
+    }
+
+    /// Returns the value of boolean `key`, as with
+    /// `git config --get --type=bool`.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        let canonical = canonical(key);
+        let entry = self.entries.iter().rev().find(|entry| entry.0 == canonical);
+        entry.map(|entry| parse_bool(key, entry.1.as_deref()))
+    }
+
+    /// Returns the values of every key that starts with `prefix` (in
+    /// lowercase, e.g. `tree.label.`), in order.
+    pub fn get_with_prefix(&self, prefix: &str) -> Vec<&str> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.0.starts_with(prefix))
+            .filter_map(|entry| entry.1.as_deref())
+            .collect()
+    }
+}
+
+/// Parses the output of `git config -z --show-scope --get-regexp`, returning the
+/// entries from the system and global configuration, then the others (from the
+/// repository, the worktree, and the command line).
+fn parse(output: &str) -> (Vec<Entry>, Vec<Entry>) {
+    let mut fields = output.split_terminator('\0');
+    let (mut shared, mut local) = (vec![], vec![]);
+    while let (Some(scope), Some(entry)) = (fields.next(), fields.next()) {
+        let parsed = match entry.split_once('\n') {
+            Some((key, value)) => (key.into(), Some(value.into())),
+            None => (entry.into(), None),
+        };
+        if matches!(scope, "system" | "global") {
+            shared.push(parsed);
+        } else {
+            local.push(parsed);
+        }
+    }
+    (shared, local)
+}
+
+/// Parses the value of boolean `key` the way git does. A key without a value is
+/// true.
+fn parse_bool(key: &str, value: Option<&str>) -> bool {
+    let parsed = match value.map(str::to_ascii_lowercase).as_deref() {
+        None | Some("true" | "yes" | "on" | "1") => Some(true),
+        Some("false" | "no" | "off" | "0" | "") => Some(false),
+        Some(_) => None,
+    };
+    assert!(parsed.is_some(), "invalid boolean value for {key}");
+    parsed.unwrap()
+}
+
+/// Returns `key` with its section and variable names in lowercase, as git
+/// prints them. Subsection names are case sensitive and left alone.
+pub fn canonical(key: &str) -> String {
+    let (head, name) = key.rsplit_once('.').unwrap_or((key, ""));
+    let name = name.to_ascii_lowercase();
+    match head.split_once('.') {
+        Some((section, subsection)) => {
+            format!("{}.{subsection}.{name}", section.to_ascii_lowercase())
+        }
+        None => format!("{}.{name}", head.to_ascii_lowercase()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonical, parse, parse_bool, Entry, Settings};
+
+    /// Returns an entry for `key` with `value`.
+    fn entry(key: &str, value: Option<&str>) -> Entry {
+        (key.into(), value.map(Into::into))
+    }
+
+    #[test]
+    fn canonical_lowercases_section_and_name() {
+        assert_eq!(canonical("tree.excludeBranch"), "tree.excludebranch");
+        assert_eq!(canonical("Tree.label.V1.Name"), "tree.label.V1.name");
+        assert_eq!(canonical("tree.alias.Review.Mode"), "tree.alias.Review.mode");
+        assert_eq!(canonical("tree"), "tree.");
+    }
+
+    #[test]
+    fn parse_bool_accepts_git_spellings() {
+        for value in [None, Some("true"), Some("Yes"), Some("ON"), Some("1")] {
+            assert!(parse_bool("tree.remotes", value), "{value:?}");
+        }
+        for value in ["false", "No", "off", "0", ""] {
+            assert!(!parse_bool("tree.remotes", Some(value)), "{value}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid boolean value for tree.remotes")]
+    fn parse_bool_rejects_other_values() {
+        parse_bool("tree.remotes", Some("maybe"));
+    }
+
+    #[test]
+    fn parse_splits_shared_from_local() {
+        let output = "global\0tree.remotes\nfalse\0local\0tree.stash\0system\0tree.a\nb\0\
+                      command\0tree.pretty\noneline\0";
+        let (shared, local) = parse(output);
+        assert_eq!(shared, [entry("tree.remotes", Some("false")), entry("tree.a", Some("b"))]);
+        assert_eq!(local, [entry("tree.stash", None), entry("tree.pretty", Some("oneline"))]);
+    }
+
+    #[test]
+    fn later_entries_take_precedence() {
+        let settings = Settings {
+            entries: vec![
+                entry("tree.pretty", Some("short")),
+                entry("tree.excludebranch", Some("wip/*")),
+                entry("tree.remotes", Some("false")),
+                entry("tree.excludebranch", Some("tmp/*")),
+                entry("tree.pretty", Some("oneline")),
+                entry("tree.remotes", None),
+            ],
+        };
+        assert_eq!(settings.get("tree.Pretty"), Some("oneline"));
+        assert_eq!(settings.get("tree.remotes"), None);
+        assert_eq!(settings.get("tree.stash"), None);
+        assert_eq!(settings.get_all("tree.excludeBranch"), ["wip/*", "tmp/*"]);
+        assert_eq!(settings.get_bool("tree.remotes"), Some(true));
+        assert_eq!(settings.get_bool("tree.stash"), None);
+        assert_eq!(settings.get_with_prefix("tree.ex"), ["wip/*", "tmp/*"]);
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::process::{exit, Child, ExitStatus};
+
+/// Waits for the user-facing `git log`, returning its exit status. SIGTERM and
+/// SIGHUP sent to git-tree (e.g. by a process manager or a closing terminal)
+/// are forwarded to it.
+#[cfg(unix)]
+pub fn wait(mut child: Child) -> ExitStatus {
+    use signal_hook::consts::{SIGHUP, SIGTERM};
+    use signal_hook::iterator::Signals;
+    use std::thread::spawn;
+
+    let mut signals = Signals::new([SIGHUP, SIGTERM]).expect("failed to install signal handlers");
+    let handle = signals.handle();
+    let pid = libc::pid_t::try_from(child.id()).expect("git pid out of range");
+    let forwarder = spawn(move || {
+        for signal in signals.forever() {
+            // SAFETY: kill() does not access memory.
+            unsafe {
+                libc::kill(pid, signal);
+            }
+        }
+    });
+    let status = child.wait().expect("failed to wait for git");
+    handle.close();
+    forwarder.join().expect("signal forwarding thread panicked");
+    status
+}
+
+/// Waits for the user-facing `git log`, returning its exit status.
+#[cfg(not(unix))]
+pub fn wait(mut child: Child) -> ExitStatus {
+    child.wait().expect("failed to wait for git")
+}
+
+/// Exits the way git did if it failed, so the caller sees git's exit status: if
+/// git died from a signal git-tree dies from the same signal, and if it exited
+/// with a non-zero status git-tree exits with that status.
+pub fn exit_like(status: ExitStatus) {
+    #[cfg(unix)]
+    {
+        use signal_hook::low_level::emulate_default_handler;
+        use std::os::unix::process::ExitStatusExt as _;
+        if let Some(signal) = status.signal() {
+            emulate_default_handler(signal).expect("failed to re-raise git's signal");
+        }
+    }
+    if let Some(code) = status.code().filter(|_| !status.success()) {
+        #[allow(clippy::exit, reason = "git-tree has nothing left to do")]
+        exit(code);
+    }
+}
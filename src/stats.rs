@@ -0,0 +1,99 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commits::{outstanding_commits, Commit};
+use crate::conventional;
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use std::collections::HashMap;
+use std::io::{stdout, Write as _};
+
+/// Implements `git-tree stats`, which summarizes the outstanding commits by
+/// author. If `tree.conventional` is set, each author's commits are also
+/// counted by their Conventional Commits type.
+/// Precondition: `buffer` must be empty.
+pub fn stats(git: &GitRunner, buffer: &mut Vec<u8>, selection: &Selection) {
+    struct AuthorStats<'commit> {
+        commits: usize,
+        branches: Vec<usize>,
+        oldest: &'commit Commit,
+        /// Number of commits of each type, indexed like `conventional::TYPES`
+        /// with an extra entry at the end for other commits.
+        types: [usize; conventional::TYPES.len() + 1],
+    }
+
+    let commits = outstanding_commits(git, buffer, selection);
+    let mut authors: HashMap<&str, AuthorStats> = HashMap::new();
+    for commit in &commits {
+        let stats = authors.entry(&commit.author).or_insert(AuthorStats {
+            commits: 0,
+            branches: vec![],
+            oldest: commit,
+            types: [0; conventional::TYPES.len() + 1],
+        });
+        stats.commits = stats.commits.saturating_add(1);
+        stats.branches.extend(&commit.branches);
+        let ty = conventional::commit_type(&commit.subject).unwrap_or(conventional::TYPES.len());
+        let count = stats.types.get_mut(ty).unwrap();
+        *count = count.saturating_add(1);
+        // Commits are visited children first, so on a tie the later commit is
+        // the older one.
+        if commit.timestamp <= stats.oldest.timestamp {
+            stats.oldest = commit;
+        }
+    }
+    let mut authors: Vec<_> = authors.into_iter().collect();
+    authors.sort_unstable_by(|a, b| b.1.commits.cmp(&a.1.commits).then_with(|| a.0.cmp(b.0)));
+    let conventional = conventional::enabled(git);
+    let mut out = stdout().lock();
+    for (author, mut stats) in authors {
+        stats.branches.sort_unstable();
+        stats.branches.dedup();
+        let mut branches: Vec<_> = stats
+            .branches
+            .iter()
+            .map(|&idx| selection.interesting_branches.get(idx).unwrap().as_str())
+            .collect();
+        branches.sort_unstable();
+        writeln!(
+            out,
+            "{author}: {} commit{} on {} branch{}{}{}",
+            stats.commits,
+            if stats.commits == 1 { "" } else { "s" },
+            branches.len(),
+            if branches.len() == 1 { "" } else { "es" },
+            if branches.is_empty() { "" } else { ": " },
+            branches.join(", "),
+        )
+        .written();
+        writeln!(
+            out,
+            "    oldest: {} {} {}",
+            stats.oldest.short_id, stats.oldest.date, stats.oldest.title
+        )
+        .written();
+        if conventional {
+            let types: Vec<_> = conventional::TYPES
+                .iter()
+                .map(|&(ty, _)| ty)
+                .chain(["other"])
+                .zip(stats.types)
+                .filter(|&(_, count)| count > 0)
+                .map(|(ty, count)| format!("{count} {ty}"))
+                .collect();
+            writeln!(out, "    types: {}", types.join(", ")).written();
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::branch_stats::branch_stats;
+use crate::dirty::summary;
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use std::io::{stdout, Write as _};
+use std::path::Path;
+
+/// Files in the git directory whose presence means an operation is in
+/// progress, with the operation's name.
+const OPERATIONS: [(&str, &str); 6] = [
+    ("rebase-merge", "rebase"),
+    ("rebase-apply", "rebase (or am)"),
+    ("MERGE_HEAD", "merge"),
+    ("CHERRY_PICK_HEAD", "cherry-pick"),
+    ("REVERT_HEAD", "revert"),
+    ("BISECT_LOG", "bisect"),
+];
+
+/// How many of the nearest interesting branches the mini-tree shows.
+const NEAREST: usize = 3;
+
+/// Implements `git-tree status`, which prints the current branch and how far
+/// it is from its upstream, any operation in progress, a summary of the
+/// uncommitted changes, and a mini-tree of `HEAD` and the interesting branches
+/// nearest to it.
+pub fn status(git: &GitRunner, selection: &Selection) {
+    let mut out = stdout().lock();
+    let current = git.query("symbolic-ref", &["--quiet", "--short", "HEAD"]);
+    let heading = current.as_deref().map_or_else(
+        || {
+            let id = git.query("rev-parse", &["--short", "HEAD"]);
+            format!("HEAD detached at {}", id.expect("HEAD is not a commit"))
+        },
+        |branch| {
+            let stats = branch_stats(git, selection, false).remove(branch);
+            let stats = stats.map(|text| format!(" ({text})")).unwrap_or_default();
+            format!("On branch {branch}{stats}")
+        },
+    );
+    writeln!(out, "{heading}").written();
+    if let Some(git_dir) = git.query("rev-parse", &["--absolute-git-dir"]) {
+        for (file, operation) in OPERATIONS {
+            if Path::new(&git_dir).join(file).exists() {
+                writeln!(out, "{operation} in progress").written();
+            }
+        }
+    }
+    if let Some(summary) = summary(git) {
+        writeln!(out, "working tree: {summary}").written();
+    }
+    // The nearest branches are those the fewest commits away from HEAD, in
+    // either direction.
+    let mut distances: Vec<_> = selection
+        .interesting_branches
+        .iter()
+        .filter(|&branch| current.as_ref() != Some(branch))
+        .filter_map(|branch| {
+            let count = git.query("rev-list", &["--count", &format!("HEAD...{branch}"), "--"]);
+            count.and_then(|count| count.parse::<usize>().ok()).map(|count| (count, branch))
+        })
+        .collect();
+    distances.sort_unstable();
+    let output = git
+        .internal("log")
+        .args(["--graph", "--oneline", "--decorate", "--simplify-by-decoration", "HEAD"])
+        .args(distances.iter().take(NEAREST).map(|entry| entry.1))
+        .args(selection.history_limit())
+        .arg("--")
+        .output()
+        .expect("failed to run git");
+    assert!(output.status.success(), "git returned unsuccessful status {}", output.status);
+    writeln!(out).written();
+    out.write_all(&output.stdout).written();
+}
@@ -0,0 +1,540 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::annotations::Annotations;
+use crate::commits::{branch_tips, displayed_commits, ghost_commits, Commit};
+use crate::ghosts::ghosts;
+use crate::git_runner::GitRunner;
+use crate::layout::{lane_order, layout, Layout};
+use crate::output::Written as _;
+use crate::selection::Selection;
+use core::ops::Range;
+use std::collections::HashMap;
+use std::io::{stdout, StdoutLock, Write as _};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Height of each commit's row, in pixels.
+const ROW: usize = 24;
+
+/// Half of `ROW`: the distance from a row's edge to its center.
+const HALF_ROW: usize = 12;
+
+/// Width of each lane, in pixels.
+const COLUMN: usize = 16;
+
+/// Radius of the commit markers.
+const RADIUS: usize = 5;
+
+/// Space around the drawing and between the graph and the text.
+const MARGIN: usize = 12;
+
+/// Approximate width of a character of text.
+const CHAR_WIDTH: usize = 8;
+
+/// Lane colors. Lanes beyond the end of the palette reuse its colors.
+const PALETTE: [&str; 8] =
+    ["#1f77b4", "#d62728", "#2ca02c", "#9467bd", "#ff7f0e", "#17becf", "#e377c2", "#8c564b"];
+
+/// Color of release milestone markers.
+const MILESTONE: &str = "#7f7f7f";
+
+/// Opacity of the commits and edges of --ghosts.
+const GHOST_OPACITY: &str = "0.35";
+
+/// Opacity of commits older than `tree.dimAfter` days, and of their edges.
+const STALE_OPACITY: &str = "0.55";
+
+/// Seconds in a day.
+const DAY: i64 = 24 * 60 * 60;
+
+/// Color of the `tree.dividers` lines and their dates.
+const DIVIDER: &str = "#aec7e8";
+
+/// Color of user-defined labels.
+const NOTE: &str = "#bcbd22";
+
+/// Color of the problems found by --lint and `tree.largeCommit`.
+const PROBLEM: &str = "#d62728";
+
+/// Color of the branches a merge base is labeled as the base of.
+const BASE_OF: &str = "#7f7f7f";
+
+/// Color of the dependency edges between branches (see `dependencies`).
+const DEPENDENCY: &str = "#9467bd";
+
+/// Colors of the --mark-contained-in annotations.
+const CONTAINED: &str = "#2ca02c";
+const NOT_CONTAINED: &str = "#d62728";
+
+/// The contents of a commit's row.
+struct Row<'commit> {
+    commit: &'commit Commit,
+
+    /// Names of the branches pointing at the commit.
+    labels: String,
+
+    /// Names of the release tags pointing at the commit.
+    milestones: String,
+
+    /// User-defined labels on the commit.
+    notes: String,
+
+    /// Problems found in the commit (see `Annotations::problems`).
+    problems: String,
+
+    /// The --mark-contained-in annotation, if any.
+    containment: String,
+
+    /// For a merge base, the branches that fork from it, if any.
+    base_of: String,
+
+    /// Whether this is the --staged pseudo-commit.
+    pseudo: bool,
+
+    /// Whether this commit is only reachable from a --ghosts tip.
+    ghost: bool,
+
+    /// Whether this commit is older than `tree.dimAfter` days.
+    stale: bool,
+}
+
+impl Row<'_> {
+    /// Returns the number of characters in this row's text.
+    #[allow(clippy::arithmetic_side_effects, reason = "bounded by the length of strings in memory")]
+    fn text_len(&self) -> usize {
+        // Non-empty labels, milestones, and notes are surrounded by brackets and
+        // preceded by a space, and problems by a space and a warning sign.
+        let bracketed = |text: &str| if text.is_empty() { 0 } else { text.chars().count() + 3 };
+        self.commit.short_id.len()
+            + bracketed(&self.labels)
+            + bracketed(&self.milestones)
+            + bracketed(&self.notes)
+            + if self.problems.is_empty() { 0 } else { self.problems.chars().count() + 3 }
+            + self.containment.chars().count()
+            + self.base_of.chars().count()
+            + 1
+            + self.commit.title.chars().count()
+    }
+}
+
+/// Implements `--format=svg`, which draws the displayed commits as a standalone
+/// SVG image. Release tags are drawn as dashed milestone lines across the rows
+/// of the commits they point to. If `staged` is set, staged changes are drawn
+/// as a pseudo-commit above HEAD. If `show_ghosts` is set, each branch's tip
+/// from before its last rebase or amend is drawn dimmed, along with the
+/// commits that are only reachable from it. Commits older than `tree.dimAfter`
+/// days are dimmed too, so that recent work stands out. Each branch that
+/// depends on another (per its tip's trailers) gets a dashed arrow from its tip
+/// to the other's.
+/// Precondition: `buffer` must be empty.
+pub fn svg(
+    git: &GitRunner,
+    buffer: &mut Vec<u8>,
+    selection: &Selection,
+    annotations: &Annotations,
+    staged: bool,
+    show_ghosts: bool,
+) {
+    let mut commits = displayed_commits(git, buffer, selection);
+    let ghosts = if show_ghosts { ghosts(git, selection) } else { vec![] };
+    let ghost_ids: Vec<_> = ghosts.iter().map(|ghost| ghost.id.as_str()).collect();
+    let ghost_commits = ghost_commits(git, buffer, selection, &ghost_ids);
+    let staged_commit = staged.then(|| staged_commit(git)).flatten();
+    let has_pseudo = staged_commit.is_some();
+    // Neither the pseudo-commit nor the ghost commits have displayed children,
+    // so they can go first.
+    let ghost_rows = Range {
+        start: usize::from(has_pseudo),
+        end: usize::from(has_pseudo).saturating_add(ghost_commits.len()),
+    };
+    commits.splice(..0, staged_commit.into_iter().chain(ghost_commits));
+    let tips = branch_tips(git, buffer, &selection.interesting_branches);
+    let layout = layout(&commits, &lane_order(git, selection, &tips));
+    let cutoff = git.settings().get("tree.dimAfter").map(stale_cutoff);
+    let rows: Vec<_> = commits
+        .iter()
+        .enumerate()
+        .map(|(row, commit)| {
+            let pseudo = has_pseudo && row == 0;
+            let mut labels: Vec<_> = tips
+                .get(&commit.id)
+                .into_iter()
+                .flatten()
+                .map(|&idx| selection.interesting_branches.get(idx).unwrap().as_str())
+                .filter(|&name| !annotations.hidden.contains(name))
+                .chain(
+                    ghosts
+                        .iter()
+                        .filter(|ghost| ghost.id == commit.id)
+                        .map(|ghost| ghost.selector.as_str()),
+                )
+                .collect();
+            labels.sort_unstable();
+            let milestones = annotations
+                .release_tags
+                .get(&commit.id)
+                .map(|tags| tags.join(", "))
+                .unwrap_or_default();
+            let notes = joined(&annotations.labels, &commit.id, ", ");
+            let problems = joined(&annotations.problems, &commit.id, "; ");
+            let containment = annotations
+                .containment
+                .as_ref()
+                .filter(|_| !pseudo)
+                .map(|containment| {
+                    let mark =
+                        if containment.contains(&commit.id) { '\u{2713}' } else { '\u{2717}' };
+                    format!(" {mark} {}", containment.refname())
+                })
+                .unwrap_or_default();
+            Row {
+                commit,
+                labels: labels.join(", "),
+                milestones,
+                notes,
+                problems,
+                containment,
+                base_of: annotations
+                    .base_of
+                    .get(&commit.id)
+                    .map(|names| format!(" base of {}", names.join(", ")))
+                    .unwrap_or_default(),
+                pseudo,
+                ghost: ghost_rows.contains(&row),
+                stale: !pseudo && cutoff.is_some_and(|cutoff| commit.timestamp < cutoff),
+            }
+        })
+        .collect();
+    let text_x = x(layout.width).saturating_add(MARGIN);
+    let text_width = rows.iter().map(Row::text_len).max().unwrap_or(0);
+    let width = text_x.saturating_add(text_width.saturating_mul(CHAR_WIDTH)).saturating_add(MARGIN);
+    let height = y(rows.len()).saturating_sub(HALF_ROW).saturating_add(MARGIN);
+
+    let mut out = stdout().lock();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="monospace" font-size="13">"#
+    )
+    .written();
+    writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#).written();
+    for (row, contents) in rows.iter().enumerate() {
+        if !contents.milestones.is_empty() {
+            let y = y(row);
+            writeln!(
+                out,
+                r#"<line x1="0" y1="{y}" x2="{width}" y2="{y}" stroke="{MILESTONE}" stroke-dasharray="4 3"/>"#
+            )
+            .written();
+        }
+    }
+    if let Some(period) = git.settings().get("tree.dividers") {
+        write_dividers(&mut out, &rows, width, period);
+    }
+    write_edges(&mut out, &layout, &rows);
+    write_dependencies(&mut out, &layout, &rows, selection, annotations, &tips);
+    for (row, (&column, contents)) in layout.columns.iter().zip(&rows).enumerate() {
+        write_row(&mut out, text_x, row, column, contents);
+    }
+    writeln!(out, "</svg>").written();
+}
+
+/// Returns the entries of `map` for commit `id` joined by `separator`, or an
+/// empty string if there are none.
+fn joined(map: &HashMap<String, Vec<String>>, id: &str, separator: &str) -> String {
+    map.get(id).map(|entries| entries.join(separator)).unwrap_or_default()
+}
+
+/// Returns a pseudo-commit representing the staged changes as a child of HEAD,
+/// or `None` if nothing is staged.
+fn staged_commit(git: &GitRunner) -> Option<Commit> {
+    let head = git.query("rev-parse", &["--verify", "--quiet", "HEAD"]);
+    let files = git.query("diff", &["--cached", "--name-only"]).unwrap_or_default();
+    let (Some(head), count @ 1..) = (head, files.lines().count()) else { return None };
+    let author = git
+        .query("var", &["GIT_AUTHOR_IDENT"])
+        .and_then(|ident| ident.split_once('>').map(|(author, _)| format!("{author}>")))
+        .unwrap_or_default();
+    let subject = format!("Staged changes ({count} file{})", if count == 1 { "" } else { "s" });
+    Some(Commit {
+        id: "index".into(),
+        short_id: "index".into(),
+        parents: vec![head],
+        author,
+        timestamp: 0,
+        date: "uncommitted".into(),
+        title: subject.clone(),
+        subject,
+        branches: vec![],
+    })
+}
+
+/// Returns the author timestamp before which commits are dimmed, given the
+/// value of `tree.dimAfter` (a number of days).
+fn stale_cutoff(days: &str) -> i64 {
+    let days: i64 = days.parse().expect("invalid tree.dimAfter");
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("clock before 1970").as_secs();
+    let now = i64::try_from(now).expect("clock out of range");
+    now.saturating_sub(days.saturating_mul(DAY))
+}
+
+/// Draws a dotted line above each commit that is in a different day or week
+/// (`period`, from `tree.dividers`) than the commit above it, labeled with the
+/// day (or the Monday of the week) it belongs to. Days are in UTC.
+fn write_dividers(out: &mut StdoutLock, rows: &[Row], width: usize, period: &str) {
+    let days = match period {
+        "day" => Some(1),
+        "week" => Some(7),
+        _ => None,
+    };
+    assert!(days.is_some(), "invalid tree.dividers {period:?} (expected day or week)");
+    // The Unix epoch was a Thursday, three days after the week began.
+    let offset = if days == Some(7) { 3 } else { 0 };
+    let mut previous = None;
+    for (row, contents) in rows.iter().enumerate() {
+        if contents.pseudo || contents.ghost {
+            continue;
+        }
+        let day = contents.commit.timestamp.div_euclid(DAY);
+        let start = day.saturating_sub(day.saturating_add(offset).rem_euclid(days.unwrap()));
+        if previous.is_some_and(|previous| previous != start) {
+            let line_y = y(row).saturating_sub(HALF_ROW);
+            let (year, month, day_of_month) = civil_date(start);
+            writeln!(
+                out,
+                r#"<line x1="0" y1="{line_y}" x2="{width}" y2="{line_y}" stroke="{DIVIDER}" stroke-dasharray="1 3"/><text x="{}" y="{}" fill="{DIVIDER}" font-size="10" text-anchor="end">{year:04}-{month:02}-{day_of_month:02}</text>"#,
+                width.saturating_sub(MARGIN),
+                line_y.saturating_add(10),
+            )
+            .written();
+        }
+        previous = Some(start);
+    }
+}
+
+/// Returns the (year, month, day) of the day `days` days after the Unix epoch,
+/// in the proleptic Gregorian calendar. This is Howard Hinnant's
+/// `civil_from_days` algorithm.
+#[allow(clippy::arithmetic_side_effects, reason = "timestamps are far from overflowing")]
+const fn civil_date(days: i64) -> (i64, i64, i64) {
+    let shifted = days + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era.div_euclid(1460) + day_of_era.div_euclid(36_524)
+        - day_of_era.div_euclid(146_096))
+    .div_euclid(365);
+    let day_of_year =
+        day_of_era - (365 * year_of_era + year_of_era.div_euclid(4) - year_of_era.div_euclid(100));
+    let month_index = (5 * day_of_year + 2).div_euclid(153);
+    let day = day_of_year - (153 * month_index + 2).div_euclid(5) + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Draws the edges between commits. An edge is dimmed like the child commit
+/// it leaves from.
+fn write_edges(out: &mut StdoutLock, layout: &Layout, rows: &[Row]) {
+    for edge in &layout.edges {
+        let child = (*layout.columns.get(edge.child).unwrap(), edge.child);
+        let parent = (*layout.columns.get(edge.parent).unwrap(), edge.parent);
+        // Leave the child toward the edge's lane, follow the lane, then join
+        // the parent.
+        let mut points = vec![child];
+        #[allow(clippy::arithmetic_side_effects, reason = "parent is after child")]
+        if parent.1 - child.1 > 1 {
+            points.push((edge.lane, child.1 + 1));
+            points.push((edge.lane, parent.1 - 1));
+        }
+        points.push(parent);
+        points.dedup();
+        let path: Vec<_> = points
+            .iter()
+            .enumerate()
+            .map(|(i, &(column, row))| {
+                format!("{}{},{}", if i == 0 { 'M' } else { 'L' }, x(column), y(row))
+            })
+            .collect();
+        let opacity = dimming(rows.get(edge.child).unwrap());
+        writeln!(
+            out,
+            r#"<path d="{}" stroke="{}" stroke-width="2" fill="none"{opacity}/>"#,
+            path.join(" "),
+            color(edge.lane)
+        )
+        .written();
+    }
+}
+
+/// Draws the dependency edges: a dashed arrow, curving to the left of the
+/// lanes, from the tip of each branch to the tip of each branch it depends on.
+fn write_dependencies(
+    out: &mut StdoutLock,
+    layout: &Layout,
+    rows: &[Row],
+    selection: &Selection,
+    annotations: &Annotations,
+    tips: &HashMap<String, Vec<usize>>,
+) {
+    // Map from branch to the (column, row) of its tip.
+    let mut positions: HashMap<&str, (usize, usize)> = HashMap::new();
+    for (row, contents) in rows.iter().enumerate() {
+        for &idx in tips.get(&contents.commit.id).into_iter().flatten() {
+            let branch = selection.interesting_branches.get(idx).unwrap().as_str();
+            positions.insert(branch, (*layout.columns.get(row).unwrap(), row));
+        }
+    }
+    let mut drawn = false;
+    for branch in &selection.interesting_branches {
+        for dependency in annotations.dependencies.get(branch).into_iter().flatten() {
+            let (Some(from), Some(to)) =
+                (positions.get(branch.as_str()), positions.get(dependency.as_str()))
+            else {
+                continue;
+            };
+            if !drawn {
+                writeln!(
+                    out,
+                    r#"<defs><marker id="dependency" viewBox="0 0 6 6" refX="6" refY="3" markerWidth="6" markerHeight="6" orient="auto"><path d="M0,0 L6,3 L0,6 z" fill="{DEPENDENCY}"/></marker></defs>"#
+                )
+                .written();
+                drawn = true;
+            }
+            let (x1, y1, x2, y2) = (x(from.0), y(from.1), x(to.0), y(to.1));
+            writeln!(
+                out,
+                r#"<path d="M{x1},{y1} C0,{y1} 0,{y2} {x2},{y2}" stroke="{DEPENDENCY}" stroke-width="1.5" stroke-dasharray="6 3" fill="none" marker-end="url(#dependency)"/>"#
+            )
+            .written();
+        }
+    }
+}
+
+/// Draws a commit's marker (with its tooltip) and text.
+fn write_row(out: &mut StdoutLock, text_x: usize, row: usize, column: usize, contents: &Row) {
+    let commit = contents.commit;
+    let (cx, cy) = (x(column), y(row));
+    // The staged pseudo-commit is drawn hollow and dashed.
+    let style = if contents.pseudo {
+        format!(
+            r#"fill="white" stroke="{}" stroke-width="2" stroke-dasharray="2 2""#,
+            color(column)
+        )
+    } else {
+        format!(r#"fill="{}""#, color(column))
+    };
+    let opacity = dimming(contents);
+    writeln!(
+        out,
+        r#"<g{opacity}><title>{}&#10;{} {}&#10;{}</title><circle cx="{cx}" cy="{cy}" r="{RADIUS}" {style}/>"#,
+        commit.id,
+        escape(&commit.author),
+        commit.date,
+        escape(&commit.subject),
+    )
+    .written();
+    write!(out, r#"<text x="{text_x}" y="{}">{}"#, cy.saturating_add(4), commit.short_id).written();
+    if !contents.labels.is_empty() {
+        write!(
+            out,
+            r#" <tspan fill="{}" font-weight="bold">({})</tspan>"#,
+            color(column),
+            escape(&contents.labels)
+        )
+        .written();
+    }
+    if !contents.milestones.is_empty() {
+        write!(
+            out,
+            r#" <tspan fill="{MILESTONE}" font-weight="bold">[{}]</tspan>"#,
+            escape(&contents.milestones)
+        )
+        .written();
+    }
+    write!(out, " {}", escape(&commit.title)).written();
+    if !contents.notes.is_empty() {
+        write!(
+            out,
+            r#" <tspan fill="{NOTE}" font-style="italic">{{{}}}</tspan>"#,
+            escape(&contents.notes)
+        )
+        .written();
+    }
+    if !contents.problems.is_empty() {
+        write!(
+            out,
+            r#" <tspan fill="{PROBLEM}">{} {}</tspan>"#,
+            '\u{26a0}',
+            escape(&contents.problems)
+        )
+        .written();
+    }
+    if !contents.containment.is_empty() {
+        let color =
+            if contents.containment.starts_with(" \u{2713}") { CONTAINED } else { NOT_CONTAINED };
+        write!(out, r#"<tspan fill="{color}">{}</tspan>"#, escape(&contents.containment)).written();
+    }
+    if !contents.base_of.is_empty() {
+        write!(
+            out,
+            r#"<tspan fill="{BASE_OF}" font-style="italic">{}</tspan>"#,
+            escape(&contents.base_of)
+        )
+        .written();
+    }
+    writeln!(out, "</text></g>").written();
+}
+
+/// Returns the attribute that dims the marker and edges of a ghost or stale
+/// commit, or nothing for other commits.
+fn dimming(row: &Row) -> String {
+    if row.ghost {
+        format!(r#" opacity="{GHOST_OPACITY}""#)
+    } else if row.stale {
+        format!(r#" opacity="{STALE_OPACITY}""#)
+    } else {
+        String::new()
+    }
+}
+
+/// Returns the x coordinate of the center of `column`.
+#[allow(clippy::arithmetic_side_effects, reason = "graph dimensions are far below usize::MAX")]
+const fn x(column: usize) -> usize {
+    MARGIN + RADIUS + column * COLUMN
+}
+
+/// Returns the y coordinate of the center of `row`.
+#[allow(clippy::arithmetic_side_effects, reason = "graph dimensions are far below usize::MAX")]
+const fn y(row: usize) -> usize {
+    MARGIN + HALF_ROW + row * ROW
+}
+
+/// Returns the color of `lane`.
+fn color(lane: usize) -> &'static str {
+    PALETTE.get(lane.checked_rem(PALETTE.len()).unwrap()).unwrap()
+}
+
+/// Escapes text for inclusion in SVG markup.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,39 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::finder::choose;
+use crate::git_runner::GitRunner;
+use crate::selection::Selection;
+use core::iter::once;
+
+/// Implements `git-tree switch`, which lets the user pick an interesting branch
+/// with `fzf` and checks it out. Remote branches are checked out through their
+/// local counterpart if there is one, and as a detached HEAD otherwise.
+pub fn switch(git: &GitRunner, selection: &Selection) {
+    let Some(branch) = choose(selection) else { return };
+    let is_local = |name: &str| {
+        git.query("show-ref", &["--verify", "--quiet", &format!("refs/heads/{name}")]).is_some()
+    };
+    let remotes = git.query("remote", &[]).unwrap_or_default();
+    let local = once(branch.as_str())
+        .chain(remotes.lines().filter_map(|remote| branch.strip_prefix(&format!("{remote}/"))))
+        .find(|&name| is_local(name));
+    let mut command = git.command("switch");
+    match local {
+        Some(local) => command.arg(local),
+        None => command.args(["--detach", &branch]),
+    };
+    let status = command.status().expect("failed to run git");
+    assert!(status.success(), "git returned unsuccessful status {status}");
+}
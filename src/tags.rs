@@ -0,0 +1,43 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use std::collections::HashMap;
+
+/// Pattern matching release tags if `tree.releaseTags` is not set.
+const DEFAULT_PATTERN: &str = "v[0-9]*";
+
+/// Returns a map from commit ID to the names of the release tags pointing at
+/// that commit. Release tags are the tags matching the `tree.releaseTags`
+/// pattern (default `v[0-9]*`).
+pub fn release_tags(git: &GitRunner) -> HashMap<String, Vec<String>> {
+    let pattern = git.settings().get("tree.releaseTags").unwrap_or(DEFAULT_PATTERN);
+    let output = git
+        .internal("for-each-ref")
+        .arg("--format=%(objectname) %(*objectname) %(refname:short)")
+        .arg(format!("refs/tags/{pattern}"))
+        .output()
+        .expect("failed to run git");
+    assert!(output.status.success(), "git returned unsuccessful status {}", output.status);
+    let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut fields = line.splitn(3, ' ');
+        let mut field = || fields.next().expect("truncated git for-each-ref output");
+        let (object, peeled, name) = (field(), field(), field());
+        // Annotated tags point to a tag object; the commit is the peeled value.
+        let commit = if peeled.is_empty() { object } else { peeled };
+        tags.entry(commit.into()).or_default().push(name.into());
+    }
+    tags
+}
@@ -0,0 +1,118 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::commits::{date_option, stream_displayed_commits};
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::selection::Selection;
+use std::collections::HashMap;
+use std::io::{stdout, Write as _};
+
+/// How many of the most recent visits to show.
+const RECENT: usize = 20;
+
+/// A stretch of HEAD's reflog spent on one branch.
+struct Visit {
+    /// The branch (or, for a detached HEAD, the commit) that was checked out.
+    branch: String,
+
+    /// When HEAD last moved during the visit.
+    date: String,
+
+    /// Where HEAD was at the end of the visit.
+    id: String,
+    short_id: String,
+    subject: String,
+}
+
+/// Implements `git-tree timeline`, which lists the branches HEAD recently
+/// visited (from its reflog), newest first, with the commit each visit ended on
+/// and the interesting branches that contain that commit.
+/// Precondition: `buffer` must be empty.
+/// Postcondition: `buffer` will be empty.
+pub fn timeline(git: &GitRunner, buffer: &mut Vec<u8>, selection: &Selection) {
+    let visits = visits(git);
+    let mut containing: HashMap<String, Vec<usize>> = HashMap::new();
+    stream_displayed_commits(git, buffer, selection, |commit| {
+        if visits.iter().any(|visit| visit.id == commit.id) {
+            containing.insert(commit.id, commit.branches);
+        }
+    });
+    let width = visits.iter().map(|visit| visit.branch.len()).max().unwrap_or_default();
+    let mut out = stdout().lock();
+    for visit in &visits {
+        let place = containing.get(&visit.id).map_or_else(
+            || "not in the tree".into(),
+            |branches| {
+                let names: Vec<_> = branches
+                    .iter()
+                    .map(|&idx| selection.interesting_branches.get(idx).unwrap().as_str())
+                    .collect();
+                format!("on {}", names.join(", "))
+            },
+        );
+        writeln!(
+            out,
+            "{}  {:width$}  {} {} ({place})",
+            visit.date, visit.branch, visit.short_id, visit.subject
+        )
+        .written();
+    }
+}
+
+/// Reads HEAD's reflog into the `RECENT` most recent visits, newest first.
+fn visits(git: &GitRunner) -> Vec<Visit> {
+    let output = git
+        .query(
+            "log",
+            &["-g", &date_option(git), "--format=%gd%x00%H%x00%h%x00%gs%x00%s", "HEAD", "--"],
+        )
+        .unwrap_or_default();
+    // Walking from the newest entry, HEAD is on the branch checked out now
+    // until a checkout entry says which branch it was on before.
+    let mut branch = git
+        .query("symbolic-ref", &["--quiet", "--short", "HEAD"])
+        .or_else(|| git.query("rev-parse", &["--short", "HEAD"]))
+        .unwrap_or_default();
+    let mut visits: Vec<Visit> = vec![];
+    for line in output.lines() {
+        let mut fields = line.splitn(5, '\0');
+        let mut field = || fields.next().expect("truncated git reflog output");
+        // With --date, the selector is HEAD@{<date>}.
+        let selector = field();
+        let date = selector
+            .strip_prefix("HEAD@{")
+            .and_then(|date| date.strip_suffix('}'))
+            .unwrap_or(selector);
+        let (id, short_id, action, subject) = (field(), field(), field(), field());
+        if visits.last().is_none_or(|last| last.branch != branch) {
+            if visits.len() == RECENT {
+                break;
+            }
+            visits.push(Visit {
+                branch: branch.clone(),
+                date: date.into(),
+                id: id.into(),
+                short_id: short_id.into(),
+                subject: subject.into(),
+            });
+        }
+        if let Some((from, _)) =
+            action.strip_prefix("checkout: moving from ").and_then(|moved| moved.split_once(" to "))
+        {
+            from.clone_into(&mut branch);
+        }
+    }
+    visits
+}
@@ -0,0 +1,41 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+
+/// Returns the repository's default branch (e.g. `origin/main`), or `None` if
+/// it cannot be determined. `tree.trunk` overrides the detection.
+pub fn default_branch(git: &GitRunner) -> Option<String> {
+    if let Some(trunk) = git.settings().get("tree.trunk") {
+        return Some(trunk.into());
+    }
+    // origin/HEAD records the remote's default branch, which is the most
+    // reliable indicator when it is present.
+    if let Some(branch) =
+        git.query("symbolic-ref", &["--quiet", "--short", "refs/remotes/origin/HEAD"])
+    {
+        return Some(branch);
+    }
+    let configured = git.query("config", &["--get", "init.defaultBranch"]);
+    for name in configured.iter().map(String::as_str).chain(["main", "master"]) {
+        for branch in [name.to_owned(), format!("origin/{name}")] {
+            let prefix = if branch == name { "refs/heads/" } else { "refs/remotes/" };
+            let refname = [prefix, &branch].concat();
+            if git.query("rev-parse", &["--verify", "--quiet", &refname]).is_some() {
+                return Some(branch);
+            }
+        }
+    }
+    None
+}
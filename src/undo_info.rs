@@ -0,0 +1,44 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use crate::output::Written as _;
+use crate::reflog::reflog;
+use std::io::{stdout, Write as _};
+
+/// How many of the branch's most recent tips to show.
+const RECENT: usize = 10;
+
+/// Implements `git-tree undo-info <branch>`, which lists local branch
+/// `branch`'s recent tips (from its reflog) with what moved the branch there,
+/// and the command that would restore each one.
+pub fn undo_info(git: &GitRunner, branch: &str) {
+    let entries = reflog(git, branch);
+    assert!(!entries.is_empty(), "{branch} is not a local branch with a reflog");
+    // The checked-out branch is moved with `git reset` so the working tree
+    // follows; --keep refuses to discard local changes.
+    let checked_out =
+        git.query("symbolic-ref", &["--quiet", "--short", "HEAD"]).as_deref() == Some(branch);
+    let mut out = stdout().lock();
+    for (i, entry) in entries.iter().take(RECENT).enumerate() {
+        writeln!(out, "{} {}  {}", entry.short_id, entry.selector, entry.subject).written();
+        if i == 0 {
+            writeln!(out, "    (current tip)").written();
+        } else if checked_out {
+            writeln!(out, "    git reset --keep {}", entry.id).written();
+        } else {
+            writeln!(out, "    git branch -f {branch} {}", entry.id).written();
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::sync::Arc;
+use core::iter::once;
+use core::time::Duration;
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread::{spawn, JoinHandle};
+
+/// A running git command that is killed if it does not finish within a
+/// timeout (see `GitRunner::spawn_watched`). This keeps git-tree from hanging
+/// forever on, e.g., a stuck credential helper or FUSE filesystem.
+pub struct Watched {
+    child: Arc<Mutex<Child>>,
+    stdout: Option<ChildStdout>,
+
+    /// A sender that is dropped to tell the watchdog thread the command
+    /// finished, and the thread itself, which returns whether it killed the
+    /// command. `None` if there is no timeout.
+    watchdog: Option<(Sender<()>, JoinHandle<bool>)>,
+
+    /// The command line, for error messages.
+    description: String,
+    timeout: Option<Duration>,
+}
+
+impl Watched {
+    /// Spawns `command` with its stdout piped.
+    pub fn spawn(command: &mut Command, timeout: Option<Duration>) -> Self {
+        let description = once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut child = command.stdout(Stdio::piped()).spawn().expect("failed to run git");
+        let stdout = child.stdout.take();
+        let child = Arc::new(Mutex::new(child));
+        let watchdog = timeout.map(|timeout| {
+            let (done, finished) = channel();
+            let child = Arc::clone(&child);
+            let thread = spawn(move || {
+                if finished.recv_timeout(timeout) != Err(RecvTimeoutError::Timeout) {
+                    return false;
+                }
+                // Killing the child closes its stdout, which unblocks the
+                // thread reading it.
+                child.lock().expect("git child lock poisoned").kill().expect("failed to kill git");
+                true
+            });
+            (done, thread)
+        });
+        Self { child, stdout, watchdog, description, timeout }
+    }
+
+    /// The command's stdout. May only be called once.
+    pub const fn stdout(&mut self) -> ChildStdout {
+        self.stdout.take().expect("stdout already taken")
+    }
+
+    /// Waits for the command to exit, asserting that it finished in time and
+    /// succeeded.
+    pub fn finish(self) {
+        if let Some((done, thread)) = self.watchdog {
+            drop(done);
+            let killed = thread.join().expect("watchdog thread panicked");
+            assert!(
+                !killed,
+                "`{}` did not finish within {} seconds (tree.timeout); killed it",
+                self.description,
+                self.timeout.unwrap_or_default().as_secs()
+            );
+        }
+        let status = self
+            .child
+            .lock()
+            .expect("git child lock poisoned")
+            .wait()
+            .expect("failed to wait for git");
+        assert!(status.success(), "git returned unsuccessful status {status}");
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright 2026 Ryan Van Why
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::git_runner::GitRunner;
+use std::fs::read_to_string;
+use std::path::Path;
+
+/// Returns refs for the detached HEADs of the other worktrees (e.g.
+/// `worktrees/review/HEAD`), so that work in progress there is displayed
+/// alongside the branches. Worktrees with a branch checked out are already
+/// covered by that branch.
+pub fn worktree_heads(git: &GitRunner) -> Vec<String> {
+    let listing = git.query("worktree", &["list", "--porcelain"]).unwrap_or_default();
+    let current = git.query("rev-parse", &["--show-toplevel"]);
+    // Each worktree is a paragraph, starting with `worktree <path>`. The first
+    // is the main worktree.
+    listing
+        .split("\n\n")
+        .enumerate()
+        .filter(|worktree| worktree.1.lines().any(|line| line == "detached"))
+        .filter_map(|(idx, worktree)| {
+            let path = worktree.lines().find_map(|line| line.strip_prefix("worktree "));
+            let Some(path) = path.filter(|path| current.as_deref() != Some(path)) else {
+                return None;
+            };
+            if idx == 0 {
+                return Some("main-worktree/HEAD".into());
+            }
+            admin_name(Path::new(path)).map(|name| format!("worktrees/{name}/HEAD"))
+        })
+        .collect()
+}
+
+/// Returns the name git uses for the linked worktree at `path`: that of its
+/// administrative directory, which the worktree's `.git` file points to. This
+/// is usually, but not always, the worktree directory's own name. Returns
+/// `None` if the worktree is missing (e.g. on an unmounted drive).
+fn admin_name(path: &Path) -> Option<String> {
+    let contents = read_to_string(path.join(".git")).unwrap_or_default();
+    let gitdir = contents.trim_end().strip_prefix("gitdir: ").map(Path::new);
+    gitdir.and_then(Path::file_name).map(|name| name.to_string_lossy().into_owned())
+}